@@ -1,9 +1,8 @@
 use aoc_utils::input;
+use aoc_utils::pattern::FromPattern;
 
-use std::str::FromStr;
-
-const INPUT: &str = include_str!("../input.txt");
-
+#[derive(FromPattern)]
+#[pattern("{0}x{1}x{2}")]
 pub struct Present(usize, usize, usize);
 
 impl Present {
@@ -22,53 +21,27 @@ impl Present {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum ParsePresentError {
-    SplitError,
-    IntegerParseError,
-}
-
-fn parse_to_int(s: &str) -> Result<usize, ParsePresentError> {
-    match s.parse() {
-        Ok(int) => Ok(int),
-        Err(_) => Err(ParsePresentError::IntegerParseError),
-    }
-}
-
-impl FromStr for Present {
-    type Err = ParsePresentError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lwh: Vec<&str> = s.split('x').collect();
-        if lwh.len() != 3 {
-            return Err(ParsePresentError::SplitError);
-        };
-        Ok(Present(
-            parse_to_int(lwh[0])?,
-            parse_to_int(lwh[1])?,
-            parse_to_int(lwh[2])?,
-        ))
-    }
-}
-
 fn part_one(input: &str) -> usize {
-    input::get_lines_of_type(input)
+    input::try_get_lines_of_type(input)
+        .expect("valid present dimensions")
         .iter()
         .map(|present: &Present| present.wrapping_paper())
         .sum()
 }
 
 fn part_two(input: &str) -> usize {
-    input::get_lines_of_type(input)
+    input::try_get_lines_of_type(input)
+        .expect("valid present dimensions")
         .iter()
         .map(|present: &Present| present.ribbon())
         .sum()
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -107,11 +80,15 @@ mod tests {
 
     #[test]
     fn part_one_input() {
-        assert_eq!(part_one(INPUT), 1586300);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 1586300);
+        }
     }
 
     #[test]
     fn part_two_sample() {
-        assert_eq!(part_two(INPUT), 3737498);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 3737498);
+        }
     }
 }
@@ -1,7 +1,6 @@
+use aoc_utils::input;
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn increment_hashmap_counter<T>(map: &mut HashMap<T, isize>, key: T)
 where
     T: std::cmp::Eq + std::cmp::PartialEq + std::hash::Hash,
@@ -53,9 +52,10 @@ fn part_two(input: &str) -> usize {
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -65,7 +65,9 @@ mod tests {
 
     #[test]
     fn input_is_correct_length() {
-        assert_eq!(INPUT.len(), 8192)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(input.len(), 8192)
+        }
     }
 
     #[test]
@@ -88,7 +90,9 @@ mod tests {
 
     #[test]
     fn part_one_actual() {
-        assert_eq!(part_one(INPUT), 2081);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 2081);
+        }
     }
 
     #[test]
@@ -100,6 +104,8 @@ mod tests {
 
     #[test]
     fn part_two_actual() {
-        assert_eq!(part_two(INPUT), 2341);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 2341);
+        }
     }
 }
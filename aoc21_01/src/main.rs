@@ -1,16 +1,15 @@
 use aoc_utils::input;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn part_one(input: &str) -> usize {
-    let nums: Vec<u32> = input::get_lines_of_type(input);
+    let nums: Vec<u32> = input::try_get_lines_of_type(input).expect("valid depths");
     nums.windows(2)
         .filter(|window| window[1] > window[0])
         .count()
 }
 
 fn part_two(input: &str) -> usize {
-    let nums: Vec<u32> = input::get_lines_of_type(input)
+    let nums: Vec<u32> = input::try_get_lines_of_type::<u32>(input)
+        .expect("valid depths")
         .windows(3)
         .map(|window| window.iter().sum())
         .collect();
@@ -20,10 +19,11 @@ fn part_two(input: &str) -> usize {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -1,8 +1,6 @@
 use aoc_utils::input;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../input.txt");
-
 #[derive(Debug, PartialEq)]
 enum Direction {
     Forward(usize),
@@ -41,7 +39,7 @@ impl FromStr for Direction {
 }
 
 fn part_one(input: &str) -> usize {
-    let directions: Vec<Direction> = input::get_lines_of_type(input);
+    let directions: Vec<Direction> = input::try_get_lines_of_type(input).expect("valid directions");
     let mut hposition: usize = 0;
     let mut depth: usize = 0;
 
@@ -56,7 +54,7 @@ fn part_one(input: &str) -> usize {
 }
 
 fn part_two(input: &str) -> usize {
-    let directions: Vec<Direction> = input::get_lines_of_type(input);
+    let directions: Vec<Direction> = input::try_get_lines_of_type(input).expect("valid directions");
     let mut hposition: usize = 0;
     let mut depth: usize = 0;
     let mut aim: usize = 0;
@@ -75,10 +73,11 @@ fn part_two(input: &str) -> usize {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -1,7 +1,5 @@
 use aoc_utils::input;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn get_counts(bit_strings: Vec<String>) -> Vec<i32> {
     let mut counts: Vec<i32> = Vec::new();
     for bit_string in bit_strings {
@@ -105,10 +103,11 @@ fn part_two(input: &str) -> i32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -143,7 +142,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 1458194)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 1458194)
+        }
     }
 
     #[test]
@@ -155,6 +156,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 2829354)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 2829354)
+        }
     }
 }
@@ -1,8 +1,6 @@
 use aoc_utils::input;
 use std::collections::HashSet;
 
-const INPUT: &str = include_str!("../input.txt");
-
 #[derive(Debug, PartialEq)]
 struct BingoBoard {
     elements: [i32; 25],
@@ -142,10 +140,11 @@ fn part_two(input: &str) -> u32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -196,11 +195,15 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 5685)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 5685)
+        }
     }
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 21070)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 21070)
+        }
     }
 }
@@ -6,12 +6,10 @@ use std::collections::HashMap;
 
 mod types;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn count_points_visited_more_than_once(input: &str, ignore_diagonal: bool) -> u32 {
     let mut count_visited_more_than_once: u32 = 0;
     let mut visit_counts: HashMap<Point, u32> = HashMap::new();
-    let lines: Vec<Line> = input::get_lines_of_type(input);
+    let lines: Vec<Line> = input::try_get_lines_of_type(input).expect("valid line segments");
     for line in lines {
         // Only consider horizontal or vertical lines
         if !ignore_diagonal | ((line.src.x == line.dst.x) | (line.src.y == line.dst.y)) {
@@ -36,10 +34,11 @@ fn part_two(input: &str) -> u32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -61,6 +60,8 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 5084)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 5084)
+        }
     }
 }
@@ -1,76 +1,20 @@
+use aoc_utils::pattern::FromPattern;
 use std::cmp::max;
-use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, FromPattern)]
+#[pattern("{x},{y}")]
 pub struct Point {
     pub x: i32,
     pub y: i32,
 }
 
-// Neither error should not be instantiated, since
-// I'm just panicking in the from_str functions
-// instead of returning Err.
-// I just like the FromStr trait.
-#[derive(Debug)]
-pub enum PointParseError {}
-#[derive(Debug)]
-pub enum LineParseError {}
-
-impl FromStr for Point {
-    type Err = PointParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.trim().split(',');
-        let x: i32 = split
-            .next()
-            .expect("String should have one element befofe one comma!")
-            .trim()
-            .parse()
-            .expect("First element of string should be an integer!");
-        let y: i32 = split
-            .next()
-            .expect("String should have one element after one comma!")
-            .trim()
-            .parse()
-            .expect("Second element of string should be an integer!");
-        match split.next() {
-            None => (),
-            Some(_) => panic!("Unexpected third element in string!"),
-        }
-        Ok(Self { x, y })
-    }
-}
-
+#[derive(FromPattern)]
+#[pattern("{src}->{dst}")]
 pub struct Line {
     pub src: Point,
     pub dst: Point,
 }
 
-impl FromStr for Line {
-    type Err = LineParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split("->");
-        let src = Point::from_str(
-            split
-                .next()
-                .expect("Line should have one element before '->'!"),
-        )
-        .expect("Point should parse from string or panic!");
-        let dst = Point::from_str(
-            split
-                .next()
-                .expect("Line should have one element after '->'!"),
-        )
-        .expect("Point should parse from string or panic!");
-        match split.next() {
-            None => (),
-            Some(_) => panic!("Unexpected third element in string!"),
-        }
-        Ok(Self { src, dst })
-    }
-}
-
 impl Line {
     pub fn get_points(self) -> Vec<Point> {
         let mut points = vec![self.src.clone()];
@@ -107,6 +51,7 @@ impl Line {
 mod tests {
 
     use super::*;
+    use std::str::FromStr;
 
     #[test]
     fn point_basic() {
@@ -133,6 +78,13 @@ mod tests {
         let _line = Line::from_str("5,6 -> 7,8").unwrap();
     }
 
+    #[test]
+    fn line_with_extra_spaces() {
+        let line = Line::from_str(" 5, 6  ->7 ,8 ").unwrap();
+        assert_eq!(line.src, Point { x: 5, y: 6 });
+        assert_eq!(line.dst, Point { x: 7, y: 8 });
+    }
+
     #[test]
     #[should_panic]
     fn line_from_str_should_panic() {
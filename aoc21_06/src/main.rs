@@ -1,7 +1,5 @@
 use aoc_utils::input;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn spawn_fish(input: &str, num_days: u64) -> u64 {
     let mut fishes = LanternSchool::new();
     for count in input.trim().split(',') {
@@ -61,10 +59,11 @@ fn part_two(input: &str) -> u64 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -93,7 +92,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 359999);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 359999);
+        }
     }
 
     #[test]
@@ -103,6 +104,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 1631647919273);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 1631647919273);
+        }
     }
 }
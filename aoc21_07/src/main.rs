@@ -1,7 +1,5 @@
 use aoc_utils::input;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn read_input(input: &str) -> Vec<u32> {
     input
         .trim()
@@ -51,10 +49,11 @@ fn part_two(input: &str) -> u32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -79,7 +78,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 349812);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 349812);
+        }
     }
 
     #[test]
@@ -89,6 +90,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 99763899);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 99763899);
+        }
     }
 }
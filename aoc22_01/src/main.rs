@@ -1,19 +1,12 @@
 use aoc_utils::input;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../input.txt");
-
-fn get_sums_of_substrings<'a>(input: &'a str) -> impl Iterator<Item = u32> + 'a {
-    input.trim().split("\n\n").map(|substring| {
-        substring
-            .split("\n")
-            .map(|string| {
-                string
-                    .parse::<u32>()
-                    .expect("Every item in input should be valid integer")
-            })
-            .sum()
+fn get_sums_of_substrings(input: &str) -> impl Iterator<Item = u32> {
+    input::parse_blocks(input, |block| {
+        block.lines().map(|line| line.parse::<u32>()).sum()
     })
+    .expect("Every item in input should be valid integer")
+    .into_iter()
 }
 
 fn part_one(input: &str) -> u32 {
@@ -30,10 +23,11 @@ fn part_two(input: &str) -> u32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -49,7 +43,9 @@ mod tests {
     }
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 71506)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 71506)
+        }
     }
 
     #[test]
@@ -59,6 +55,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 209603)
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 209603)
+        }
     }
 }
@@ -1,8 +1,6 @@
 use aoc_utils::input;
 use std::collections::{HashMap, HashSet};
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn get_priority_of_line(line: &str) -> u32 {
     let n = line.chars().count() / 2;
     let (first, second): (Vec<_>, Vec<_>) = line.chars().enumerate().partition(|(i, _)| i < &n);
@@ -48,10 +46,11 @@ fn part_two(input: &str) -> u32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -74,7 +73,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 7997);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 7997);
+        }
     }
 
     #[test]
@@ -84,6 +85,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 2545);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 2545);
+        }
     }
 }
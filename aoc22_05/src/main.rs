@@ -2,8 +2,6 @@ use aoc_utils::input;
 
 mod parse;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn part_one(input: &str) -> String {
     let (stack_lines, instructions) = input
         .split_once("\n\n")
@@ -53,10 +51,11 @@ fn part_two(input: &str) -> String {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -73,7 +72,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), "TLFGBZHCN")
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), "TLFGBZHCN")
+        }
     }
 
     #[test]
@@ -83,6 +84,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), "QRQFHFWCL")
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), "QRQFHFWCL")
+        }
     }
 }
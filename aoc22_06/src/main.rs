@@ -1,8 +1,7 @@
+use aoc_utils::input;
 use itertools::Itertools;
 use std::collections::VecDeque;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn part_one(input: &str) -> usize {
     find_marker(input, 4)
 }
@@ -26,9 +25,10 @@ fn find_marker(input: &str, size: usize) -> usize {
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -46,10 +46,14 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 1953);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 1953);
+        }
     }
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 2301);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 2301);
+        }
     }
 }
@@ -1,4 +1,5 @@
-use aoc_utils::tree::ArenaTree;
+use aoc_utils::tree::{Keyed, KeyedArenaTree, NodeId};
+use std::fmt;
 
 #[derive(Debug)]
 pub enum FSError {
@@ -12,64 +13,56 @@ pub enum FSNode<'a> {
     Directory(&'a str),
 }
 
-impl<'a> FSNode<'a> {
-    pub fn name(&self) -> &'a str {
+impl<'a> Keyed for FSNode<'a> {
+    type Key = &'a str;
+
+    fn key(&self) -> &&'a str {
         match self {
-            FSNode::File(name, _) => *name,
-            FSNode::Directory(name) => *name,
+            FSNode::File(name, _) => name,
+            FSNode::Directory(name) => name,
         }
     }
 }
 
+impl<'a> fmt::Display for FSNode<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
 pub struct FileSystem<'a> {
-    tree: ArenaTree<FSNode<'a>>,
-    cwd: usize,
+    tree: KeyedArenaTree<FSNode<'a>>,
+    cwd: NodeId,
 }
 
 impl<'a> FileSystem<'a> {
     pub fn new() -> FileSystem<'a> {
-        let mut tree = ArenaTree::new();
+        let mut tree = KeyedArenaTree::new();
         let root_node = FSNode::Directory("/");
-        tree.add_node(root_node);
-        let cwd = 0;
+        let cwd = tree.add_node(root_node);
         FileSystem { tree, cwd }
     }
 
     pub fn exists(&mut self, name: &str) -> bool {
-        for child_idx in self
-            .tree
-            .get_node(self.cwd)
+        self.tree
+            .find_child_by_key(self.cwd, name)
             .expect("cwd is always valid")
-            .children()
-        {
-            let node = self
-                .tree
-                .get_node(*child_idx)
-                .expect("child node idx is valid when iterating over .children");
-            if node.value.name() == name {
-                return true;
-            }
-        }
-        false
+            .is_some()
     }
 
     pub fn cd_root(&mut self) {
-        self.cwd = 0;
+        self.cwd = self.tree.root_of(self.cwd).expect("cwd is always valid");
     }
 
-    fn size(&self, idx: usize) -> usize {
-        let node = self
-            .tree
-            .get_node(idx)
-            .expect("Private function size not called without valid idx");
-        match node.value {
-            FSNode::File(_, size) => size,
-            FSNode::Directory(_) => node
-                .children()
-                .iter()
-                .map(|child_idx| self.size(*child_idx))
-                .sum(),
-        }
+    fn size(&self, idx: NodeId) -> usize {
+        self.tree
+            .pre_order(idx)
+            .expect("Private function size not called without valid idx")
+            .map(|node| match node.value {
+                FSNode::File(_, size) => size,
+                FSNode::Directory(_) => 0,
+            })
+            .sum()
     }
 
     pub fn duh(&self) -> usize {
@@ -104,43 +97,55 @@ impl<'a> FileSystem<'a> {
                 }
             }
         }
-        for child_idx in self
+        match self
             .tree
-            .get_node(self.cwd)
+            .find_child_by_key(self.cwd, name)
             .expect("cwd is always valid")
-            .children()
         {
-            let node = self
-                .tree
-                .get_node(*child_idx)
-                .expect("child node idx is valid when iterating over .children");
-            if node.value.name() == name {
-                self.cwd = node.idx();
-                return Ok(());
+            Some(node) => {
+                self.cwd = node.id();
+                Ok(())
             }
+            None => Err(FSError::DirectoryNotFound),
         }
-        Err(FSError::DirectoryNotFound)
+    }
+
+    fn all_sizes(&self) -> Vec<Option<usize>> {
+        self.tree
+            .fold_up(|node, child_sizes: &[&usize]| match node.value {
+                FSNode::File(_, size) => size,
+                FSNode::Directory(_) => child_sizes.iter().copied().sum(),
+            })
     }
 
     pub fn rget_all_dir_sizes(&self) -> Vec<usize> {
+        let sizes = self.all_sizes();
         let mut res = Vec::new();
-        for idx in 0..self.tree.len() {
-            match self
-                .tree
-                .get_node(idx)
-                .expect(
-                    "idx is valid when iterating over indicies less than the length of the tree",
-                )
-                .value
-            {
+        for (node, size) in self.tree.iter().zip(sizes.into_iter().flatten()) {
+            match node.value {
                 FSNode::File(_, _) => (),
-                FSNode::Directory(_) => res.push(self.size(idx)),
+                FSNode::Directory(_) => res.push(size),
             }
         }
         res
     }
 }
 
+/// Render the file system like the listing in the puzzle, with total sizes for directories
+impl<'a> fmt::Display for FileSystem<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sizes = self.all_sizes();
+        let rendered = self.tree.render_with(|node| {
+            let size = sizes[node.id().index()].expect("Nodes are never removed");
+            Some(match node.value {
+                FSNode::File(_, _) => format!("file, size={}", size),
+                FSNode::Directory(_) => format!("dir, total size={}", size),
+            })
+        });
+        write!(f, "{}", rendered)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -162,5 +167,22 @@ mod tests {
         fs.cd_root();
         assert_eq!(fs.duh(), 1100);
         assert_eq!(fs.rget_all_dir_sizes(), vec![1100, 1100, 0, 1100, 500]);
+        fs.tree.validate().unwrap();
+    }
+
+    #[test]
+    fn filesystem_display() {
+        let mut fs = FileSystem::new();
+        fs.mkdir("a");
+        fs.touch("b.txt", 10);
+        fs.cd("a").unwrap();
+        fs.touch("c", 5);
+        assert_eq!(
+            fs.to_string(),
+            "/ (dir, total size=15)\n\
+             ├── a (dir, total size=5)\n\
+             │   └── c (file, size=5)\n\
+             └── b.txt (file, size=10)\n"
+        );
     }
 }
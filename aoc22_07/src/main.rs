@@ -1,8 +1,6 @@
 use aoc_utils::input;
 mod fs;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn parse_input(input: &str) -> fs::FileSystem {
     let mut lines = input.trim().lines();
     if lines.next().expect("Input should have at least one line") != "$ cd /" {
@@ -67,10 +65,11 @@ fn part_two(input: &str) -> usize {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -93,7 +92,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 1367870);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 1367870);
+        }
     }
 
     #[test]
@@ -103,6 +104,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 549173);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 549173);
+        }
     }
 }
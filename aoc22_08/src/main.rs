@@ -1,8 +1,6 @@
 use aoc_utils::input;
 use std::collections::{HashMap, HashSet};
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn parse_input(input: &str) -> (Vec<Vec<i8>>, Vec<Vec<i8>>) {
     let (mut rows, mut columns) = (Vec::new(), Vec::new());
     for line in input.lines() {
@@ -108,10 +106,11 @@ fn part_two(input: &str) -> usize {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -145,7 +144,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 1807);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 1807);
+        }
     }
 
     #[test]
@@ -155,6 +156,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 480000);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 480000);
+        }
     }
 }
@@ -1,8 +1,6 @@
 use aoc_utils::input;
 use std::iter::Iterator;
 
-const INPUT: &str = include_str!("../input.txt");
-
 enum Instruction {
     Noop,
     Addx(i32),
@@ -55,10 +53,11 @@ fn part_two(input: &str) -> String {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -75,7 +74,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 13520);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 13520);
+        }
     }
 
     #[test]
@@ -85,6 +86,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), "###...##..###..#..#.###..####..##..###..#..#.#..#.#..#.#..#.#..#.#....#..#.#..#.#..#.#....#..#.####.###..###..#..#.###..###..#.##.###..#..#.#..#.#....####.#..#.#....#..#.#....#..#.#..#.#....#..#.#..#.#.....###.#....#..#.###..####.#..#.###..");
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), "###...##..###..#..#.###..####..##..###..#..#.#..#.#..#.#..#.#..#.#....#..#.#..#.#..#.#....#..#.####.###..###..#..#.###..###..#.##.###..#..#.#..#.#....####.#..#.#....#..#.#....#..#.#..#.#....#..#.#..#.#.....###.#....#..#.###..####.#..#.###..");
+        }
     }
 }
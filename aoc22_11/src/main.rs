@@ -1,10 +1,9 @@
 use crate::monkey::*;
+use aoc_utils::input;
 use num_integer::lcm;
 
 mod monkey;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn simulate_round(monkeys: &mut Vec<Monkey>, part_one: bool, lcm: i64) {
     for i in 0..monkeys.len() {
         let monkey = &mut monkeys[i];
@@ -65,9 +64,10 @@ fn part_two(input: &str) -> u64 {
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -84,7 +84,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 107822);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 107822);
+        }
     }
 
     #[test]
@@ -94,6 +96,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 27267163742);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 27267163742);
+        }
     }
 }
@@ -1,58 +1,59 @@
-use serde_json;
-use serde_json::Value;
-use serde_json::Value::*;
+use aoc_utils::input;
+use aoc_utils::tree::{ArenaTree, Delimiters, Nested, NodeId};
 use std::cmp::Ordering;
 
-const INPUT: &str = include_str!("../input.txt");
+type Packets = ArenaTree<Nested<u64>>;
 
-fn parse_line(line: &str) -> Value {
-    serde_json::from_str(line)
-        .expect("Each line in the input represents a valid (nested) JSON array")
+fn parse_line(packets: &mut Packets, line: &str) -> NodeId {
+    packets
+        .add_nested(line, Delimiters::default(), str::parse::<u64>)
+        .expect("Each line in the input represents a valid nested list")
 }
 
-fn in_order(left: &Value, right: &Value) -> Option<bool> {
-    match (left, right) {
-        (Number(l), Number(r)) => {
-            let l = l.as_u64().unwrap();
-            let r = r.as_u64().unwrap();
-            if l < r {
-                Some(true)
-            } else if l > r {
-                Some(false)
-            } else {
-                None
-            }
+fn in_order(packets: &Packets, left: NodeId, right: NodeId) -> Option<bool> {
+    match compare(packets, left, right) {
+        Ordering::Less => Some(true),
+        Ordering::Greater => Some(false),
+        Ordering::Equal => None,
+    }
+}
+
+fn compare(packets: &Packets, left: NodeId, right: NodeId) -> Ordering {
+    match (&packets[left], &packets[right]) {
+        (Nested::Leaf(l), Nested::Leaf(r)) => l.cmp(r),
+        (Nested::Leaf(_), Nested::List) => {
+            compare_lists(packets, &[left], children(packets, right))
         }
-        (Number(l), Array(_)) => in_order(&Value::from(vec![l.as_u64().unwrap()]), right),
-        (Array(_), Number(r)) => in_order(left, &Value::from(vec![r.as_u64().unwrap()])),
-        (Array(l), Array(r)) => {
-            for i in 0.. {
-                let l = l.get(i);
-                let r = r.get(i);
-                match (l, r) {
-                    (None, None) => return None,
-                    (None, Some(_)) => return Some(true),
-                    (Some(_), None) => return Some(false),
-                    (Some(l), Some(r)) => match in_order(l, r) {
-                        None => (),
-                        Some(bool_) => return Some(bool_),
-                    },
-                }
-            }
-            None
+        (Nested::List, Nested::Leaf(_)) => {
+            compare_lists(packets, children(packets, left), &[right])
+        }
+        (Nested::List, Nested::List) => {
+            compare_lists(packets, children(packets, left), children(packets, right))
         }
-        _ => panic!("Not expecting any JSON `Value`s other than `Number` and `Array`!"),
     }
 }
 
+fn compare_lists(packets: &Packets, left: &[NodeId], right: &[NodeId]) -> Ordering {
+    left.iter()
+        .zip(right)
+        .map(|(&l, &r)| compare(packets, l, r))
+        .find(|&ordering| ordering != Ordering::Equal)
+        .unwrap_or_else(|| left.len().cmp(&right.len()))
+}
+
+fn children(packets: &Packets, idx: NodeId) -> &[NodeId] {
+    packets.get_node(idx).expect("Packet exists").children()
+}
+
 fn part_one(input: &str) -> usize {
+    let mut packets = Packets::new();
     input.split("\n\n").enumerate().fold(0, |a, (i, pair)| {
         let (left, right) = pair
             .split_once('\n')
             .expect("Input sample is two lines followed by a blank line");
-        let left = parse_line(left);
-        let right = parse_line(right);
-        match in_order(&left, &right) {
+        let left = parse_line(&mut packets, left);
+        let right = parse_line(&mut packets, right);
+        match in_order(&packets, left, right) {
             None => panic!(),
             Some(true) => a + i + 1,
             Some(false) => a,
@@ -61,35 +62,34 @@ fn part_one(input: &str) -> usize {
 }
 
 fn part_two(input: &str) -> usize {
-    let mut values: Vec<Value> = input
+    let mut packets = Packets::new();
+    let mut ids: Vec<NodeId> = input
         .lines()
         .filter(|line| line != &"")
-        .map(|line| {
-            line.parse()
-                .expect("Each line is a valid nested JSON array")
-        })
+        .map(|line| parse_line(&mut packets, line))
         .collect();
-    values.push("[[2]]".parse().unwrap());
-    values.push("[[6]]".parse().unwrap());
-    values.sort_by(|l, r| match in_order(l, r) {
+    let dividers = [
+        parse_line(&mut packets, "[[2]]"),
+        parse_line(&mut packets, "[[6]]"),
+    ];
+    ids.extend(dividers);
+    ids.sort_by(|&l, &r| match in_order(&packets, l, r) {
         None => panic!(),
         Some(true) => Ordering::Less,
         Some(false) => Ordering::Greater,
     });
-    values
-        .iter()
+    ids.iter()
         .enumerate()
-        .filter(|(_, v)| {
-            (*v == &"[[2]]".parse::<Value>().unwrap()) || (*v == &"[[6]]".parse::<Value>().unwrap())
-        })
+        .filter(|(_, id)| dividers.contains(id))
         .map(|(i, _)| i + 1)
         .product()
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -103,7 +103,10 @@ mod tests {
     const SAMPLE2: &str = "[]\n[[]]\n[[[]]]\n[1,1,3,1,1]\n[1,1,5,1,1]\n[[1],[2,3,4]]\n[1,[2,[3,[4,[5,6,0]]]],8,9]\n[1,[2,[3,[4,[5,6,7]]]],8,9]\n[[1],4]\n[[2]]\n[3]\n[[4,4],4,4]\n[[4,4],4,4,4]\n[[6]]\n[7,7,7]\n[7,7,7,7]\n[[8,7,6]]\n[9]";
 
     fn strings_in_order(l: &str, r: &str) -> bool {
-        in_order(&l.parse().unwrap(), &r.parse().unwrap()).unwrap()
+        let mut packets = Packets::new();
+        let l = parse_line(&mut packets, l);
+        let r = parse_line(&mut packets, r);
+        in_order(&packets, l, r).unwrap()
     }
 
     #[test]
@@ -131,7 +134,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT), 5292);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input), 5292);
+        }
     }
 
     #[test]
@@ -141,6 +146,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT), 23868);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input), 23868);
+        }
     }
 }
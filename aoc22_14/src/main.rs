@@ -1,7 +1,6 @@
+use aoc_utils::input;
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../input.txt");
-
 #[derive(Debug, PartialEq, Eq)]
 enum Tile {
     Source,
@@ -209,9 +208,10 @@ fn run(input: &str, part_two: bool) -> usize {
 }
 
 fn main() {
-    let part_one_solution = run(INPUT, false);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = run(&input, false);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = run(INPUT, true);
+    let part_two_solution = run(&input, true);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -238,7 +238,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(run(INPUT, false), 862);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(run(&input, false), 862);
+        }
     }
 
     #[test]
@@ -248,6 +250,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(run(INPUT, true), 28744);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(run(&input, true), 28744);
+        }
     }
 }
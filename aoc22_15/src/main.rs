@@ -1,11 +1,7 @@
 use aoc_utils::input;
-use lazy_static::lazy_static;
-use regex::Regex;
 use std::collections::HashSet;
 use std::ops::Add;
 
-const INPUT: &str = include_str!("../input.txt");
-
 #[derive(Debug, PartialEq, Eq)]
 struct Sensor {
     x: i32,
@@ -40,18 +36,8 @@ impl Sensor {
 }
 
 fn parse_line(line: &str) -> Sensor {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(
-            r#"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)"#
-        )
-        .unwrap();
-    }
-    let captures = RE.captures(line).expect("Line matches regex");
-
-    let x = captures.get(1).unwrap().as_str().parse().unwrap();
-    let y = captures.get(2).unwrap().as_str().parse().unwrap();
-    let bx = captures.get(3).unwrap().as_str().parse().unwrap();
-    let by = captures.get(4).unwrap().as_str().parse().unwrap();
+    let [x, y, bx, by] =
+        input::get_signed_ints_array(line).expect("Line contains sensor and beacon coordinates");
 
     Sensor { x, y, bx, by }
 }
@@ -202,10 +188,11 @@ fn part_two(input: &str, limit: i32) -> i64 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT, 2000000);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input, 2000000);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT, 4000000);
+    let part_two_solution = part_two(&input, 4000000);
     println!("Solution to part two: {:?}", part_two_solution);
 }
 
@@ -336,7 +323,9 @@ mod tests {
 
     #[test]
     fn part_one_solution() {
-        assert_eq!(part_one(INPUT, 2000000), 4582667);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_one(&input, 2000000), 4582667);
+        }
     }
 
     #[test]
@@ -346,6 +335,8 @@ mod tests {
 
     #[test]
     fn part_two_solution() {
-        assert_eq!(part_two(INPUT, 4000000), 10961118625406);
+        if let Some(input) = input::load_file_for_test(env!("CARGO_MANIFEST_DIR")) {
+            assert_eq!(part_two(&input, 4000000), 10961118625406);
+        }
     }
 }
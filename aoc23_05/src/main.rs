@@ -1,13 +1,11 @@
+use aoc_utils::input;
+use aoc_utils::parse::{field, run_parser, sections, unsigned, ws_list, ParseError};
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, char, digit1, newline};
-use nom::combinator::map_res;
+use nom::character::complete::{alpha1, char, newline};
 use nom::multi::separated_list1;
-use nom::sequence::{preceded, separated_pair, terminated, tuple};
+use nom::sequence::{separated_pair, terminated, tuple};
 use nom::IResult;
 use std::cmp::min;
-use std::str::FromStr;
-
-const INPUT: &str = include_str!("../input.txt");
 
 #[allow(dead_code)] // src and dst not actually read
 #[derive(Debug)]
@@ -42,12 +40,8 @@ impl<'a> ChainMap<'a> for Vec<Map<'a>> {
     }
 }
 
-fn number<T: FromStr>(input: &str) -> IResult<&str, T> {
-    map_res(digit1, str::parse)(input)
-}
-
 fn parse_seeds(input: &str) -> IResult<&str, Vec<u64>> {
-    preceded(tag("seeds: "), separated_list1(char(' '), number))(input)
+    field("seeds", ws_list(unsigned))(input)
 }
 
 fn parse_map(input: &str) -> IResult<&str, Map> {
@@ -57,9 +51,9 @@ fn parse_map(input: &str) -> IResult<&str, Map> {
         separated_list1(
             newline,
             tuple((
-                terminated(number, char(' ')),
-                terminated(number, char(' ')),
-                number,
+                terminated(unsigned, char(' ')),
+                terminated(unsigned, char(' ')),
+                unsigned,
             )),
         ),
     )(input)?;
@@ -73,25 +67,21 @@ fn parse_map(input: &str) -> IResult<&str, Map> {
     ))
 }
 
-fn parse_input(input: &str) -> IResult<&str, (Vec<u64>, Vec<Map>)> {
-    terminated(
-        separated_pair(
-            parse_seeds,
-            tag("\n\n"),
-            separated_list1(tag("\n\n"), parse_map),
-        ),
-        tag("\n"),
-    )(input)
+fn parse_input(input: &str) -> Result<(Vec<u64>, Vec<Map>), ParseError> {
+    run_parser(
+        separated_pair(parse_seeds, tag("\n\n"), sections(parse_map)),
+        input,
+    )
 }
 
 pub fn part_one(input: &str) -> u64 {
-    let (_, (seeds, maps)) = parse_input(input).unwrap();
+    let (seeds, maps) = parse_input(input).unwrap();
     seeds.iter().map(|seed| maps.map(*seed)).min().unwrap()
 }
 
 fn part_two(input: &str) -> u64 {
     let mut res = u64::MAX;
-    let (_, (seeds, maps)) = parse_input(input).unwrap();
+    let (seeds, maps) = parse_input(input).unwrap();
     for pair in seeds.chunks(2) {
         for num in pair[0]..(pair[0] + pair[1]) {
             res = min(res, maps.map(num));
@@ -101,9 +91,10 @@ fn part_two(input: &str) -> u64 {
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -131,8 +122,7 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        let (input, (seeds, maps)) = parse_input(SAMPLE).unwrap();
-        assert_eq!(input, "");
+        let (seeds, maps) = parse_input(SAMPLE).unwrap();
         assert_eq!(seeds, vec![79, 14, 55, 13]);
         assert_eq!(maps.last().unwrap().dst, "location");
         assert_eq!(
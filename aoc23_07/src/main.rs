@@ -4,8 +4,6 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../input.txt");
-
 #[derive(Debug, PartialEq, Eq, Hash)]
 struct Card<const JOKERS: bool> {
     char: char,
@@ -244,7 +242,7 @@ impl FromStr for Hand<true> {
 }
 
 fn part_one(input: &str) -> u32 {
-    let mut hands: Vec<Hand<false>> = input::get_lines_of_type(input);
+    let mut hands: Vec<Hand<false>> = input::try_get_lines_of_type(input).expect("valid hands");
     hands.sort();
     hands
         .iter()
@@ -254,7 +252,7 @@ fn part_one(input: &str) -> u32 {
 }
 
 fn part_two(input: &str) -> u32 {
-    let mut hands: Vec<Hand<true>> = input::get_lines_of_type(input);
+    let mut hands: Vec<Hand<true>> = input::try_get_lines_of_type(input).expect("valid hands");
     hands.sort();
     hands
         .iter()
@@ -264,10 +262,11 @@ fn part_two(input: &str) -> u32 {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Head of input:\n{:?}", input::head(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
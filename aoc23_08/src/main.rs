@@ -1,12 +1,13 @@
+use aoc_utils::input;
+use aoc_utils::parse::{run_parser, ParseError};
 use nom::bytes::complete::tag;
-use nom::character::complete::alpha1;
+use nom::character::complete::{alpha1, line_ending};
+use nom::multi::separated_list1;
 use nom::sequence::{delimited, separated_pair};
 use nom::IResult;
 use num::integer::lcm;
 use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn node_parser(input: &str) -> IResult<&str, Node> {
     let (input, (name, (left, right))) = separated_pair(
         alpha1,
@@ -20,6 +21,17 @@ fn node_parser(input: &str) -> IResult<&str, Node> {
     Ok((input, Node { name, left, right }))
 }
 
+fn parse_input(input: &str) -> Result<(&str, Vec<Node<'_>>), ParseError> {
+    run_parser(
+        separated_pair(
+            alpha1,
+            tag("\n\n"),
+            separated_list1(line_ending, node_parser),
+        ),
+        input,
+    )
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct Node<'a> {
     name: &'a str,
@@ -57,23 +69,15 @@ fn compute_path_length(
 }
 
 fn part_one(input: &str) -> u64 {
-    let (instructions, nodes) = input.split_once("\n\n").unwrap();
-    let mut node_map = HashMap::new();
-    for node in nodes.lines() {
-        let (_, node) = node_parser(node).unwrap();
-        node_map.insert(node.name, node);
-    }
+    let (instructions, nodes) = parse_input(input).unwrap();
+    let node_map: HashMap<&str, Node> = nodes.into_iter().map(|node| (node.name, node)).collect();
     let node = node_map.get("AAA").unwrap();
     compute_path_length(node, instructions, &node_map, "ZZZ").unwrap()
 }
 
 fn part_two(input: &str) -> u64 {
-    let (instructions, nodes) = input.split_once("\n\n").unwrap();
-    let mut node_map = HashMap::new();
-    for node in nodes.lines() {
-        let (_, node) = node_parser(node).unwrap();
-        node_map.insert(node.name, node);
-    }
+    let (instructions, nodes) = parse_input(input).unwrap();
+    let node_map: HashMap<&str, Node> = nodes.into_iter().map(|node| (node.name, node)).collect();
     let lengths: Vec<u64> = node_map
         .values()
         .into_iter()
@@ -88,18 +92,13 @@ struct PathLengthError;
 
 #[allow(dead_code, unused_mut, unused_variables)]
 fn part_two_old(input: &str) -> u32 {
-    let (instructions, nodes) = input.split_once("\n\n").unwrap();
+    let (instructions, nodes) = parse_input(input).unwrap();
     let mut current_nodes: Vec<&str> = nodes
-        .lines()
-        .map(|line| node_parser(line).unwrap().1.name)
+        .iter()
+        .map(|node| node.name)
         .filter(|name| name.ends_with('A'))
         .collect();
-    let node_map: HashMap<&str, Node> = HashMap::from_iter(
-        nodes
-            .lines()
-            .map(|line| node_parser(line).unwrap().1)
-            .map(|node| (node.name, node)),
-    );
+    let node_map: HashMap<&str, Node> = nodes.into_iter().map(|node| (node.name, node)).collect();
     let mut count = 0;
     for instruction in instructions.chars().cycle() {
         if current_nodes
@@ -124,9 +123,10 @@ fn part_two_old(input: &str) -> u32 {
 }
 
 fn main() {
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    let part_two_solution = part_two(INPUT);
+    let part_two_solution = part_two(&input);
     println!("Solution to part two: {}", part_two_solution);
 }
 
@@ -152,6 +152,23 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_input() {
+        let (instructions, nodes) = parse_input(SAMPLE).unwrap();
+        assert_eq!(instructions, "LLR");
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(
+            nodes[2],
+            Node {
+                name: "ZZZ",
+                left: "ZZZ",
+                right: "ZZZ"
+            }
+        );
+        let err = parse_input("LLR\n\nAAA = (BBB BBB)").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 11));
+    }
+
     #[test]
     fn test_lcm_vec() {
         let vec = vec![1, 3, 4, 2];
@@ -0,0 +1,64 @@
+//! Compare `HashMapMaze` with `Grid` on the hill climb from 2022 day 12.
+//!
+//! Uses the input saved in `aoc22_12` if there is one, and the example from the puzzle
+//! otherwise. Run with `cargo bench -p aoc_utils`.
+
+use aoc_utils::input;
+use aoc_utils::maze::{bfs, Grid, HashMapMaze, ValuedMaze, YAxis};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const SAMPLE: &str = "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi";
+
+fn height(c: char) -> Option<u8> {
+    match c {
+        'S' => Some(0),
+        'E' => Some(25),
+        'a'..='z' => Some(c as u8 - b'a'),
+        _ => None,
+    }
+}
+
+/// Fewest steps from start to end climbing at most one step at a time.
+fn climb<M>(maze: &M, start: (usize, usize), end: (usize, usize)) -> Option<usize>
+where
+    M: ValuedMaze<Location = (usize, usize), Value = u8>,
+{
+    bfs(maze, start, |&loc| loc == end, |&from, &to| to <= from + 1).map(|path| path.cost)
+}
+
+fn bench_climb(c: &mut Criterion) {
+    let input = input::load_file(concat!(env!("CARGO_MANIFEST_DIR"), "/../aoc22_12"))
+        .unwrap_or_else(|_| SAMPLE.to_string());
+    let (maze, [start, end]) =
+        HashMapMaze::from_char_grid(&input, YAxis::BottomUp, ['S', 'E'], height).unwrap();
+    let (grid, _) = Grid::from_char_grid(&input, YAxis::BottomUp, ['S', 'E'], height).unwrap();
+
+    let mut group = c.benchmark_group("aoc22_12 climb");
+    group.bench_function("HashMapMaze", |b| {
+        b.iter(|| climb(&maze, black_box(start), end))
+    });
+    group.bench_function("Grid", |b| b.iter(|| climb(&grid, black_box(start), end)));
+    group.finish();
+
+    let mut group = c.benchmark_group("aoc22_12 lookups");
+    group.bench_function("HashMapMaze", |b| {
+        b.iter(|| {
+            (0..grid.height())
+                .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+                .map(|loc| *maze.get_value_at_loc(black_box(&loc)).unwrap() as u32)
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("Grid", |b| {
+        b.iter(|| {
+            (0..grid.height())
+                .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+                .map(|loc| grid[black_box(loc)] as u32)
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_climb);
+criterion_main!(benches);
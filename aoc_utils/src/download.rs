@@ -0,0 +1,358 @@
+#![warn(missing_docs)]
+
+use std::env;
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Base URL of the Advent of Code website.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// User-Agent sent with every request, as requested by the Advent of Code maintainers.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "aoc_utils/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/bpshaver/advent-of-code-rust)"
+);
+
+/// Name of the environment variable read by `Downloader::from_env` for the session token.
+pub const SESSION_ENV_VAR: &str = "AOC_SESSION";
+
+/// Name of the environment variable read by `Downloader::from_env` for the cache directory.
+pub const CACHE_DIR_ENV_VAR: &str = "AOC_CACHE_DIR";
+
+/// Error type for downloading puzzle inputs.
+#[derive(Debug)]
+pub enum DownloadError {
+    /// Raised when the requested year or day is not a valid Advent of Code puzzle
+    InvalidPuzzle {
+        /// Requested year
+        year: u16,
+        /// Requested day
+        day: u8,
+    },
+    /// Raised when no session token is available
+    MissingSession,
+    /// Raised when the server rejects the session token, usually because it has expired
+    InvalidSession,
+    /// Raised when the requested puzzle has not unlocked yet
+    NotUnlocked {
+        /// Requested year
+        year: u16,
+        /// Requested day
+        day: u8,
+    },
+    /// Raised for any other unsuccessful HTTP response
+    Http {
+        /// HTTP status code of the response
+        status: u16,
+        /// Body of the response
+        body: String,
+    },
+    /// Raised when the server could not be reached
+    Transport(String),
+    /// Raised when the cache could not be read or written
+    Io(io::Error),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::InvalidPuzzle { year, day } => {
+                write!(f, "there is no puzzle for year {} day {}", year, day)
+            }
+            DownloadError::MissingSession => {
+                write!(f, "no session token given; set ${}", SESSION_ENV_VAR)
+            }
+            DownloadError::InvalidSession => write!(f, "session token was rejected; log in again"),
+            DownloadError::NotUnlocked { year, day } => {
+                write!(
+                    f,
+                    "puzzle for year {} day {} has not unlocked yet",
+                    year, day
+                )
+            }
+            DownloadError::Http { status, body } => {
+                write!(f, "unexpected HTTP status {}: {}", status, body.trim())
+            }
+            DownloadError::Transport(e) => write!(f, "request failed: {}", e),
+            DownloadError::Io(e) => write!(f, "cache error: {}", e),
+        }
+    }
+}
+
+impl Error for DownloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DownloadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DownloadError {
+    fn from(e: io::Error) -> Self {
+        DownloadError::Io(e)
+    }
+}
+
+/// Downloads puzzle inputs and caches them on disk, so that each day is only ever requested
+/// once.
+///
+/// # Examples
+/// ```no_run
+/// use aoc_utils::download::Downloader;
+///
+/// let downloader = Downloader::from_env().unwrap();
+/// let input = downloader.get_input(2022, 1).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    session: String,
+    cache_dir: PathBuf,
+    base_url: String,
+    user_agent: String,
+}
+
+impl Downloader {
+    /// Create a `Downloader` with a session token and a directory to cache inputs in.
+    pub fn new(session: impl Into<String>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            session: session.into(),
+            cache_dir: cache_dir.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+
+    /// Create a `Downloader` from the `AOC_SESSION` and `AOC_CACHE_DIR` environment variables.
+    ///
+    /// If `AOC_CACHE_DIR` is not set, inputs are cached in `aoc` under `$XDG_CACHE_HOME`, or
+    /// `$HOME/.cache` if that is not set, or `std::env::temp_dir()` if neither is. As the XDG
+    /// spec requires, an empty or relative `$XDG_CACHE_HOME` is ignored. Will return an error if
+    /// `AOC_SESSION` is not set.
+    pub fn from_env() -> Result<Self, DownloadError> {
+        let session = env::var(SESSION_ENV_VAR).map_err(|_| DownloadError::MissingSession)?;
+        let cache_dir = match env::var_os(CACHE_DIR_ENV_VAR) {
+            Some(dir) => PathBuf::from(dir),
+            None => default_cache_dir(env::var_os("XDG_CACHE_HOME"), env::var_os("HOME")),
+        };
+        Ok(Self::new(session, cache_dir))
+    }
+
+    /// Use a different base URL, e.g. a local stub server in tests.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Use a different User-Agent header.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Get the path at which the input for `year` and `day` is cached.
+    pub fn cache_path(&self, year: u16, day: u8) -> PathBuf {
+        self.cache_dir
+            .join(year.to_string())
+            .join(format!("day{:02}.txt", day))
+    }
+
+    /// Get the puzzle input for `year` and `day`, from the cache if it is there and from the
+    /// server otherwise.
+    ///
+    /// Will return an error if the puzzle does not exist, the session token is rejected, the
+    /// puzzle has not unlocked yet or the request otherwise fails. Failed responses are never
+    /// cached.
+    pub fn get_input(&self, year: u16, day: u8) -> Result<String, DownloadError> {
+        if year < 2015 || !(1..=25).contains(&day) {
+            return Err(DownloadError::InvalidPuzzle { year, day });
+        }
+        let path = self.cache_path(year, day);
+        if path.is_file() {
+            return Ok(fs::read_to_string(path)?);
+        }
+        let input = self.fetch(year, day)?;
+        let dir = path
+            .parent()
+            .expect("Cache path is inside the cache directory");
+        fs::create_dir_all(dir)?;
+        // Write to a temporary file first so that an interrupted write never leaves a
+        // truncated input in the cache.
+        let tmp_path = path.with_extension("txt.tmp");
+        fs::write(&tmp_path, &input)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(input)
+    }
+
+    fn fetch(&self, year: u16, day: u8) -> Result<String, DownloadError> {
+        if self.session.is_empty() {
+            return Err(DownloadError::MissingSession);
+        }
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(&self.user_agent)
+            .build();
+        match agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+        {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(match status {
+                    400 | 401 | 403 => DownloadError::InvalidSession,
+                    404 => DownloadError::NotUnlocked { year, day },
+                    _ => DownloadError::Http { status, body },
+                })
+            }
+            Err(ureq::Error::Transport(e)) => Err(DownloadError::Transport(e.to_string())),
+        }
+    }
+}
+
+/// Directory inputs are cached in when `AOC_CACHE_DIR` is not set, given the values of
+/// `$XDG_CACHE_HOME` and `$HOME`.
+fn default_cache_dir(xdg_cache_home: Option<OsString>, home: Option<OsString>) -> PathBuf {
+    xdg_cache_home
+        .filter(|dir| Path::new(dir).is_absolute())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir)
+        .join("aoc")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    /// Serve each of `responses` to one request in turn, and send back each request's head.
+    fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut head = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                tx.send(head).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (base_url, rx)
+    }
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc_utils_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn default_cache_dir_fallbacks() {
+        let home = || Some(OsString::from("/home/elf"));
+        assert_eq!(
+            default_cache_dir(Some("/xdg".into()), home()),
+            PathBuf::from("/xdg/aoc")
+        );
+        assert_eq!(
+            default_cache_dir(Some("".into()), home()),
+            PathBuf::from("/home/elf/.cache/aoc")
+        );
+        assert_eq!(
+            default_cache_dir(Some("relative".into()), home()),
+            PathBuf::from("/home/elf/.cache/aoc")
+        );
+        assert_eq!(default_cache_dir(None, None), env::temp_dir().join("aoc"));
+    }
+
+    #[test]
+    fn get_input_downloads_then_caches() {
+        let (base_url, requests) = stub_server(vec![(200, "1\n2\n3\n")]);
+        let downloader = Downloader::new("abc123", temp_cache_dir("cache"))
+            .with_base_url(base_url)
+            .with_user_agent("test-agent");
+
+        assert_eq!(downloader.get_input(2022, 1).unwrap(), "1\n2\n3\n");
+        let head = requests.recv().unwrap();
+        assert!(head.starts_with("GET /2022/day/1/input "));
+        assert!(head.contains("session=abc123"));
+        assert!(head.contains("test-agent"));
+        assert!(downloader.cache_path(2022, 1).is_file());
+
+        // The stub server only answers once, so this must come from the cache.
+        assert_eq!(downloader.get_input(2022, 1).unwrap(), "1\n2\n3\n");
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn get_input_typed_errors() {
+        let (base_url, _requests) = stub_server(vec![
+            (400, "Please log in"),
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+            (500, "Oops"),
+        ]);
+        let downloader =
+            Downloader::new("expired", temp_cache_dir("errors")).with_base_url(base_url);
+
+        assert!(matches!(
+            downloader.get_input(2022, 1),
+            Err(DownloadError::InvalidSession)
+        ));
+        assert!(matches!(
+            downloader.get_input(2022, 2),
+            Err(DownloadError::NotUnlocked { year: 2022, day: 2 })
+        ));
+        assert!(matches!(
+            downloader.get_input(2022, 3),
+            Err(DownloadError::Http { status: 500, .. })
+        ));
+        assert!(!downloader.cache_path(2022, 1).exists());
+    }
+
+    #[test]
+    fn get_input_rejects_bad_puzzles_without_requesting() {
+        let downloader =
+            Downloader::new("", temp_cache_dir("invalid")).with_base_url("http://127.0.0.1:9");
+        assert!(matches!(
+            downloader.get_input(2022, 26),
+            Err(DownloadError::InvalidPuzzle { .. })
+        ));
+        assert!(matches!(
+            downloader.get_input(2014, 1),
+            Err(DownloadError::InvalidPuzzle { .. })
+        ));
+        assert!(matches!(
+            downloader.get_input(2022, 1),
+            Err(DownloadError::MissingSession)
+        ));
+    }
+}
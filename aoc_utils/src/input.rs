@@ -1,7 +1,59 @@
 #![warn(missing_docs)]
 
+use std::collections::BTreeSet;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::marker::PhantomData;
+use std::num::ParseIntError;
+use std::path::Path;
 use std::str::FromStr;
 
+/// Name of the environment variable checked by `load` for the path to a puzzle input.
+pub const INPUT_ENV_VAR: &str = "AOC_INPUT";
+
+/// Name of the file, relative to a crate's manifest directory, checked by `load` and
+/// `load_file`.
+pub const INPUT_FILE_NAME: &str = "input.txt";
+
+/// Error returned when a line of input fails to parse as the requested type.
+///
+/// Carries the 1-based line number, the offending text and the underlying
+/// `FromStr` error.
+#[derive(Debug, PartialEq)]
+pub struct ParseLinesError<E> {
+    /// 1-based line number of the line that failed to parse
+    pub line: usize,
+    /// Text of the line that failed to parse
+    pub text: String,
+    /// Error returned by `FromStr` for this line
+    pub source: E,
+}
+
+impl<E> fmt::Display for ParseLinesError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse line {} ({:?}): {}",
+            self.line, self.text, self.source
+        )
+    }
+}
+
+impl<E> Error for ParseLinesError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
 /// Return the first 5 lines of the input string as a vector.
 ///
 /// # Examples
@@ -20,7 +72,9 @@ pub fn head(s: &str) -> Vec<&str> {
 /// Return a vector of items of type T from an input string split
 /// on newlines.
 ///
-/// T must implement the std::FromStr trait.
+/// T must implement the std::FromStr trait. Lines which fail to parse are
+/// silently skipped; see `try_get_lines_of_type` for a version which fails
+/// loudly on malformed input.
 ///
 /// # Examples
 ///
@@ -32,22 +86,705 @@ pub fn head(s: &str) -> Vec<&str> {
 /// assert_eq!(nums, vec![1,2,3]);
 /// ```
 pub fn get_lines_of_type<T>(input: &str) -> Vec<T>
+where
+    T: FromStr,
+{
+    get_lines_of_type_lenient(input).0
+}
+
+/// Return a vector of items of type T from an input string split
+/// on newlines, or an error describing the first line which fails to parse.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let nums: Vec<i32> = input::try_get_lines_of_type("1\n2\n3").unwrap();
+/// assert_eq!(nums, vec![1,2,3]);
+///
+/// let err = input::try_get_lines_of_type::<i32>("1\ntwo\n3").unwrap_err();
+/// assert_eq!(err.line, 2);
+/// assert_eq!(err.text, "two");
+/// ```
+pub fn try_get_lines_of_type<T>(input: &str) -> Result<Vec<T>, ParseLinesError<T::Err>>
+where
+    T: FromStr,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse().map_err(|source| ParseLinesError {
+                line: i + 1,
+                text: line.to_string(),
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Return a vector of items of type T from an input string split on
+/// newlines, along with an error for every line which failed to parse.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let (nums, skipped) = input::get_lines_of_type_lenient::<u8>("1\nfoo\n3\n");
+///
+/// assert_eq!(nums, vec![1,3]);
+/// assert_eq!(skipped.len(), 1);
+/// assert_eq!(skipped[0].line, 2);
+/// ```
+pub fn get_lines_of_type_lenient<T>(input: &str) -> (Vec<T>, Vec<ParseLinesError<T::Err>>)
 where
     T: FromStr,
 {
     let mut res: Vec<T> = Vec::new();
-    for line in input.lines() {
+    let mut skipped = Vec::new();
+    for (i, line) in input.lines().enumerate() {
         match line.parse() {
             Ok(item) => res.push(item),
-            Err(_) => continue,
+            Err(source) => skipped.push(ParseLinesError {
+                line: i + 1,
+                text: line.to_string(),
+                source,
+            }),
         }
     }
+    (res, skipped)
+}
+
+/// Error returned when a blank-line-separated block of input fails to parse.
+#[derive(Debug, PartialEq)]
+pub struct ParseBlocksError<E> {
+    /// 0-based index of the block which failed to parse
+    pub block: usize,
+    /// 1-based line number of the first line of the block
+    pub line: usize,
+    /// Text of the block which failed to parse
+    pub text: String,
+    /// Error returned by the block parser
+    pub source: E,
+}
+
+impl<E> fmt::Display for ParseBlocksError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse block {} (starting on line {}): {}",
+            self.block, self.line, self.source
+        )
+    }
+}
+
+impl<E> Error for ParseBlocksError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Like `get_blocks`, but also return the 1-based line number each block starts on.
+fn blocks_with_line_numbers(input: &str) -> Vec<(usize, String)> {
+    let mut res = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if let Some((start, lines)) = current.take() {
+                res.push((start, lines.join("\n")));
+            }
+        } else {
+            current
+                .get_or_insert_with(|| (i + 1, Vec::new()))
+                .1
+                .push(line);
+        }
+    }
+    if let Some((start, lines)) = current {
+        res.push((start, lines.join("\n")));
+    }
     res
 }
 
+/// Split an input string into blocks of lines separated by one or more blank lines.
+///
+/// Lines containing only whitespace count as blank, `\r\n` line endings are
+/// normalized to `\n`, and leading or trailing blank lines are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let input = "1\n2\n\n\n3\r\n  \n4\n5\n";
+///
+/// assert_eq!(input::get_blocks(input), vec!["1\n2", "3", "4\n5"]);
+/// ```
+pub fn get_blocks(input: &str) -> Vec<String> {
+    blocks_with_line_numbers(input)
+        .into_iter()
+        .map(|(_, block)| block)
+        .collect()
+}
+
+/// Parse each blank-line-separated block of an input string with the closure `f`.
+///
+/// Returns an error describing the first block for which `f` fails.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let sums = input::parse_blocks("1\n2\n\n3", |block| {
+///     block.lines().map(|line| line.parse::<u32>()).sum::<Result<u32, _>>()
+/// })
+/// .unwrap();
+///
+/// assert_eq!(sums, vec![3, 3]);
+/// ```
+pub fn parse_blocks<T, E, F>(input: &str, mut f: F) -> Result<Vec<T>, ParseBlocksError<E>>
+where
+    F: FnMut(&str) -> Result<T, E>,
+{
+    blocks_with_line_numbers(input)
+        .into_iter()
+        .enumerate()
+        .map(|(block, (line, text))| match f(&text) {
+            Ok(item) => Ok(item),
+            Err(source) => Err(ParseBlocksError {
+                block,
+                line,
+                text,
+                source,
+            }),
+        })
+        .collect()
+}
+
+/// Return a vector of items of type T from an input string split on blank lines.
+///
+/// T must implement the std::FromStr trait. Returns an error describing the
+/// first block which fails to parse.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let words: Vec<String> = input::get_blocks_of_type("a\nb\n\nc").unwrap();
+///
+/// assert_eq!(words, vec!["a\nb", "c"]);
+/// ```
+pub fn get_blocks_of_type<T>(input: &str) -> Result<Vec<T>, ParseBlocksError<T::Err>>
+where
+    T: FromStr,
+{
+    parse_blocks(input, str::parse)
+}
+
+/// Error returned when integers cannot be extracted from an input string.
+#[derive(Debug, PartialEq)]
+pub enum ExtractIntsError {
+    /// Raised when an integer in the input does not fit in the requested type
+    ParseInt(ParseIntError),
+    /// Raised when the input contains a different number of integers than expected
+    WrongCount {
+        /// Number of integers expected
+        expected: usize,
+        /// Number of integers found in the input
+        found: usize,
+    },
+}
+
+impl fmt::Display for ExtractIntsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractIntsError::ParseInt(e) => write!(f, "failed to parse integer: {}", e),
+            ExtractIntsError::WrongCount { expected, found } => {
+                write!(f, "expected {} integers, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for ExtractIntsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExtractIntsError::ParseInt(e) => Some(e),
+            ExtractIntsError::WrongCount { .. } => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for ExtractIntsError {
+    fn from(e: ParseIntError) -> Self {
+        ExtractIntsError::ParseInt(e)
+    }
+}
+
+/// Iterator over the integers embedded in a string, created by `signed_ints` or
+/// `unsigned_ints`.
+///
+/// Yields an error for any integer which does not fit in `T`.
+pub struct Ints<'a, T> {
+    s: &'a str,
+    pos: usize,
+    signed: bool,
+    _type: PhantomData<T>,
+}
+
+impl<'a, T> Iterator for Ints<'a, T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    type Item = Result<T, ParseIntError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.s.as_bytes();
+        let mut start = self.pos;
+        while start < bytes.len() && !bytes[start].is_ascii_digit() {
+            start += 1;
+        }
+        if start == bytes.len() {
+            self.pos = start;
+            return None;
+        }
+        let mut end = start;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+        self.pos = end;
+        // A minus sign only counts as a sign if it doesn't directly follow a digit, so that
+        // ranges like "2-4" are read as two positive integers.
+        if self.signed
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_digit())
+        {
+            start -= 1;
+        }
+        Some(self.s[start..end].parse())
+    }
+}
+
+/// Iterate over the signed integers embedded in a string, without allocating.
+///
+/// A `-` immediately before a number is treated as a minus sign unless it
+/// directly follows another digit, so `"x=-2"` yields `-2` but `"2-4"` yields
+/// `2` and `4`. Newlines are treated like any other separator, so this works on
+/// a single line or a whole input.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let nums: Vec<i32> = input::signed_ints("Sensor at x=2, y=-18")
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(nums, vec![2, -18]);
+/// ```
+pub fn signed_ints<T>(s: &str) -> Ints<'_, T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    Ints {
+        s,
+        pos: 0,
+        signed: true,
+        _type: PhantomData,
+    }
+}
+
+/// Iterate over the unsigned integers embedded in a string, without allocating.
+///
+/// Every `-` is treated as a separator.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let nums: Vec<u8> = input::unsigned_ints("2-4,6-8")
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(nums, vec![2, 4, 6, 8]);
+/// ```
+pub fn unsigned_ints<T>(s: &str) -> Ints<'_, T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    Ints {
+        s,
+        pos: 0,
+        signed: false,
+        _type: PhantomData,
+    }
+}
+
+/// Return a vector of all signed integers embedded in a string.
+///
+/// See `signed_ints` for how minus signs are handled.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let nums: Vec<i64> = input::get_signed_ints("1,-2\n-3 -> 4").unwrap();
+///
+/// assert_eq!(nums, vec![1, -2, -3, 4]);
+/// ```
+pub fn get_signed_ints<T>(s: &str) -> Result<Vec<T>, ExtractIntsError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    Ok(signed_ints(s).collect::<Result<_, _>>()?)
+}
+
+/// Return a vector of all unsigned integers embedded in a string.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let nums: Vec<usize> = input::get_unsigned_ints("move 1 from 2 to 3").unwrap();
+///
+/// assert_eq!(nums, vec![1, 2, 3]);
+/// ```
+pub fn get_unsigned_ints<T>(s: &str) -> Result<Vec<T>, ExtractIntsError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    Ok(unsigned_ints(s).collect::<Result<_, _>>()?)
+}
+
+fn to_array<T, const N: usize>(v: Vec<T>) -> Result<[T; N], ExtractIntsError> {
+    let found = v.len();
+    v.try_into()
+        .map_err(|_| ExtractIntsError::WrongCount { expected: N, found })
+}
+
+/// Return exactly `N` signed integers embedded in a string as an array.
+///
+/// Will return an error if the string contains more or fewer than `N` integers.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let [x, y]: [i32; 2] = input::get_signed_ints_array("x=-2, y=18").unwrap();
+/// assert_eq!((x, y), (-2, 18));
+///
+/// assert!(input::get_signed_ints_array::<i32, 3>("x=-2, y=18").is_err());
+/// ```
+pub fn get_signed_ints_array<T, const N: usize>(s: &str) -> Result<[T; N], ExtractIntsError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    to_array(get_signed_ints(s)?)
+}
+
+/// Return exactly `N` unsigned integers embedded in a string as an array.
+///
+/// Will return an error if the string contains more or fewer than `N` integers.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let [a, b, c, d]: [u32; 4] = input::get_unsigned_ints_array("2-4,6-8").unwrap();
+///
+/// assert_eq!((a, b, c, d), (2, 4, 6, 8));
+/// ```
+pub fn get_unsigned_ints_array<T, const N: usize>(s: &str) -> Result<[T; N], ExtractIntsError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    to_array(get_unsigned_ints(s)?)
+}
+
+/// Error returned when puzzle input cannot be loaded.
+#[derive(Debug)]
+pub enum LoadInputError {
+    /// Raised when an explicitly requested input could not be read
+    Io {
+        /// Where the input was being read from
+        source_name: String,
+        /// Underlying IO error
+        error: io::Error,
+    },
+    /// Raised when no input was found in any of the places searched
+    NotFound {
+        /// Descriptions of every place that was searched, in order
+        searched: Vec<String>,
+    },
+}
+
+impl fmt::Display for LoadInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadInputError::Io { source_name, error } => {
+                write!(f, "failed to read input from {}: {}", source_name, error)
+            }
+            LoadInputError::NotFound { searched } => {
+                write!(f, "no puzzle input found; searched:")?;
+                for place in searched {
+                    write!(f, "\n  - {}", place)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for LoadInputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadInputError::Io { error, .. } => Some(error),
+            LoadInputError::NotFound { .. } => None,
+        }
+    }
+}
+
+fn read_path(path: &Path) -> Result<String, LoadInputError> {
+    fs::read_to_string(path).map_err(|error| LoadInputError::Io {
+        source_name: path.display().to_string(),
+        error,
+    })
+}
+
+fn read_stdin(stdin: &mut dyn Read) -> Result<String, LoadInputError> {
+    let mut buf = String::new();
+    stdin
+        .read_to_string(&mut buf)
+        .map_err(|error| LoadInputError::Io {
+            source_name: "stdin".to_string(),
+            error,
+        })?;
+    Ok(buf)
+}
+
+fn load_from(
+    arg: Option<String>,
+    env_path: Option<String>,
+    stdin: &mut dyn Read,
+    stdin_is_piped: bool,
+    default_path: &Path,
+) -> Result<String, LoadInputError> {
+    match arg.as_deref() {
+        Some("-") => return read_stdin(stdin),
+        Some(path) => return read_path(Path::new(path)),
+        None => (),
+    }
+    let mut searched = vec!["command line argument (none given)".to_string()];
+    match env_path {
+        Some(path) if !path.is_empty() => return read_path(Path::new(&path)),
+        _ => searched.push(format!("${} (not set)", INPUT_ENV_VAR)),
+    }
+    // Stdin is only read as a last resort, since a pipe which is never closed would block
+    if default_path.is_file() {
+        return read_path(default_path);
+    }
+    searched.push(format!("{} (not found)", default_path.display()));
+    if stdin_is_piped {
+        let input = read_stdin(stdin)?;
+        if !input.is_empty() {
+            return Ok(input);
+        }
+        searched.push("stdin (empty)".to_string());
+    } else {
+        searched.push("stdin (not piped)".to_string());
+    }
+    Err(LoadInputError::NotFound { searched })
+}
+
+/// Load puzzle input at runtime.
+///
+/// The input is taken from the first of these which is available:
+///
+/// 1. A path given as the first command line argument, or stdin if that argument is `-`
+/// 2. A path given in the `AOC_INPUT` environment variable
+/// 3. `input.txt` in `manifest_dir`, which should be `env!("CARGO_MANIFEST_DIR")`
+/// 4. Stdin, if it is piped and not empty
+///
+/// Stdin is only read when nothing else is available, so pass `-` as the argument to read it
+/// even if `input.txt` exists.
+///
+/// Will return an error listing every place searched if no input is found.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aoc_utils::input;
+///
+/// let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+/// ```
+pub fn load(manifest_dir: &str) -> Result<String, LoadInputError> {
+    let stdin = io::stdin();
+    let stdin_is_piped = !stdin.is_terminal();
+    load_from(
+        env::args().nth(1),
+        env::var(INPUT_ENV_VAR).ok(),
+        &mut stdin.lock(),
+        stdin_is_piped,
+        &Path::new(manifest_dir).join(INPUT_FILE_NAME),
+    )
+}
+
+/// Load puzzle input from `input.txt` in `manifest_dir` only.
+///
+/// Unlike `load`, this ignores command line arguments, the environment and stdin, so it is
+/// suitable for tests which check answers against a crate's own input.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// assert!(input::load_file("/does/not/exist").is_err());
+/// ```
+pub fn load_file(manifest_dir: &str) -> Result<String, LoadInputError> {
+    let path = Path::new(manifest_dir).join(INPUT_FILE_NAME);
+    if path.is_file() {
+        read_path(&path)
+    } else {
+        Err(LoadInputError::NotFound {
+            searched: vec![format!("{} (not found)", path.display())],
+        })
+    }
+}
+
+/// Load puzzle input from `input.txt` in `manifest_dir` for a test, or `None` to skip the test.
+///
+/// A missing `input.txt` prints a message saying the test is skipped. Any other error, such as
+/// an unreadable file, panics so that the test fails rather than silently passing.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// if let Some(input) = input::load_file_for_test("/does/not/exist") {
+///     unreachable!("{}", input);
+/// }
+/// ```
+pub fn load_file_for_test(manifest_dir: &str) -> Option<String> {
+    match load_file(manifest_dir) {
+        Ok(input) => Some(input),
+        Err(LoadInputError::NotFound { searched }) => {
+            println!("skipping test, no puzzle input: {}", searched.join(", "));
+            None
+        }
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// Summary of the shape of an input string, returned by `summarize`.
+#[derive(Debug, PartialEq)]
+pub struct InputSummary {
+    /// Number of lines
+    pub line_count: usize,
+    /// Number of characters in the shortest line
+    pub min_width: usize,
+    /// Number of characters in the longest line
+    pub max_width: usize,
+    /// Whether every line is non-empty and the same width
+    pub is_grid: bool,
+    /// Every distinct character in the input, other than line endings
+    pub distinct_chars: BTreeSet<char>,
+    /// Number of blocks separated by blank lines
+    pub block_count: usize,
+    /// Smallest and largest signed integers in the input, if there are any which fit in an `i64`
+    pub int_range: Option<(i64, i64)>,
+}
+
+impl fmt::Display for InputSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "lines: {} (width {}..={}{})",
+            self.line_count,
+            self.min_width,
+            self.max_width,
+            if self.is_grid {
+                ", rectangular grid"
+            } else {
+                ""
+            }
+        )?;
+        write!(f, "\nblocks: {}", self.block_count)?;
+        write!(
+            f,
+            "\ndistinct chars ({}): {:?}",
+            self.distinct_chars.len(),
+            self.distinct_chars.iter().collect::<String>()
+        )?;
+        match self.int_range {
+            Some((min, max)) => write!(f, "\nintegers: {}..={}", min, max),
+            None => write!(f, "\nintegers: none"),
+        }
+    }
+}
+
+/// Summarize the shape of an input string, to help pick a parsing strategy and catch
+/// truncated or wrong-day inputs before solving.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::input;
+///
+/// let summary = input::summarize("#.#\n.-1\n..9\n");
+///
+/// assert_eq!(summary.line_count, 3);
+/// assert!(summary.is_grid);
+/// assert_eq!(summary.int_range, Some((-1, 9)));
+///
+/// println!("{}", summary);
+/// ```
+pub fn summarize(input: &str) -> InputSummary {
+    let widths: Vec<usize> = input.lines().map(|line| line.chars().count()).collect();
+    let min_width = widths.iter().copied().min().unwrap_or(0);
+    let max_width = widths.iter().copied().max().unwrap_or(0);
+    let int_range = signed_ints::<i64>(input)
+        .filter_map(Result::ok)
+        .fold(None, |range, n| match range {
+            None => Some((n, n)),
+            Some((min, max)) => Some((n.min(min), n.max(max))),
+        });
+    InputSummary {
+        line_count: widths.len(),
+        min_width,
+        max_width,
+        is_grid: min_width > 0 && min_width == max_width,
+        distinct_chars: input
+            .chars()
+            .filter(|c| !matches!(c, '\n' | '\r'))
+            .collect(),
+        block_count: blocks_with_line_numbers(input).len(),
+        int_range,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
 
     #[test]
     fn head_empty() {
@@ -96,4 +833,254 @@ mod tests {
         let result: Vec<u32> = get_lines_of_type(sample);
         assert_eq!(result, vec![1, 2])
     }
+
+    #[test]
+    fn try_get_lines_of_type_all_ints() {
+        let result: Vec<u8> = try_get_lines_of_type("1\n2\n3\n").unwrap();
+        assert_eq!(result, vec![1, 2, 3])
+    }
+
+    #[test]
+    fn try_get_lines_of_type_reports_first_bad_line() {
+        let err = try_get_lines_of_type::<u32>("1\n2\nthree\nfour").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.text, "three");
+        assert_eq!(err.source, "three".parse::<u32>().unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            "failed to parse line 3 (\"three\"): invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn try_get_lines_of_type_boxes_into_dyn_error() {
+        fn parse(s: &str) -> Result<Vec<u32>, Box<dyn Error>> {
+            Ok(try_get_lines_of_type(s)?)
+        }
+        assert!(parse("1\nx").is_err());
+    }
+
+    #[test]
+    fn get_lines_of_type_lenient_collects_skipped() {
+        let (result, skipped) = get_lines_of_type_lenient::<u32>("1\n\nthree\n4");
+        assert_eq!(result, vec![1, 4]);
+        assert_eq!(
+            skipped.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(skipped[1].text, "three");
+    }
+
+    #[test]
+    fn get_blocks_empty() {
+        let empty_vec: Vec<String> = vec![];
+        assert_eq!(get_blocks(""), empty_vec);
+        assert_eq!(get_blocks("\n \n\t\n"), empty_vec);
+    }
+
+    #[test]
+    fn get_blocks_tolerates_crlf_and_blank_runs() {
+        let sample = "\r\na\r\nb\r\n\r\n \r\n\r\nc\r\n\r\n";
+        assert_eq!(get_blocks(sample), vec!["a\nb", "c"])
+    }
+
+    #[test]
+    fn get_blocks_preserves_leading_whitespace() {
+        let sample = "    [D]\n[N] [C]\n\nmove 1 from 2 to 1";
+        assert_eq!(
+            get_blocks(sample),
+            vec!["    [D]\n[N] [C]", "move 1 from 2 to 1"]
+        )
+    }
+
+    #[test]
+    fn parse_blocks_reports_block_index_and_line() {
+        let sample = "1\n2\n\n3\n\n\nfour\n5";
+        let err = parse_blocks(sample, |block| {
+            block
+                .lines()
+                .map(|line| line.parse::<u32>())
+                .collect::<Result<Vec<u32>, _>>()
+        })
+        .unwrap_err();
+        assert_eq!(err.block, 2);
+        assert_eq!(err.line, 7);
+        assert_eq!(err.text, "four\n5");
+        assert_eq!(
+            err.to_string(),
+            "failed to parse block 2 (starting on line 7): invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn get_blocks_of_type_ints() {
+        let result: Vec<u32> = get_blocks_of_type("1\n\n2\n\n3").unwrap();
+        assert_eq!(result, vec![1, 2, 3]);
+        assert!(get_blocks_of_type::<u32>("1\n2").is_err());
+    }
+
+    #[test]
+    fn signed_ints_minus_handling() {
+        let result: Vec<i32> = get_signed_ints("-1 2-3 x=-4,y=-5 --6 a-7").unwrap();
+        assert_eq!(result, vec![-1, 2, 3, -4, -5, -6, -7])
+    }
+
+    #[test]
+    fn unsigned_ints_ignore_minus() {
+        let result: Vec<u32> = get_unsigned_ints("-1 2-3\nx=-4").unwrap();
+        assert_eq!(result, vec![1, 2, 3, 4])
+    }
+
+    #[test]
+    fn ints_none() {
+        let empty_vec: Vec<u8> = vec![];
+        assert_eq!(get_unsigned_ints::<u8>("").unwrap(), empty_vec);
+        assert_eq!(
+            get_signed_ints::<u8>("no numbers - here").unwrap(),
+            empty_vec
+        );
+    }
+
+    #[test]
+    fn ints_overflow_is_error() {
+        assert!(matches!(
+            get_unsigned_ints::<u8>("1 256"),
+            Err(ExtractIntsError::ParseInt(_))
+        ));
+        assert!(get_signed_ints::<u32>("x=-1").is_err());
+    }
+
+    #[test]
+    fn ints_array_wrong_count() {
+        assert_eq!(
+            get_signed_ints_array::<i32, 4>("1,2 -> 3"),
+            Err(ExtractIntsError::WrongCount {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            get_signed_ints_array::<i32, 3>("1,2 -> 3").unwrap(),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn ints_iterator_is_lazy() {
+        let mut iter = unsigned_ints::<u64>("1 2 3");
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.next(), Some(Ok(2)));
+        assert_eq!(iter.next(), Some(Ok(3)));
+        assert_eq!(iter.next(), None);
+    }
+
+    fn temp_input(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc_utils_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn load_from_precedence() {
+        let arg = temp_input("arg.txt", "arg");
+        let env_path = temp_input("env.txt", "env");
+        let default = temp_input("default.txt", "default");
+        let arg_str = || Some(arg.display().to_string());
+        let env_str = || Some(env_path.display().to_string());
+
+        let load = |arg, env, stdin: &str, piped| {
+            load_from(arg, env, &mut stdin.as_bytes(), piped, &default).unwrap()
+        };
+        assert_eq!(load(arg_str(), env_str(), "stdin", true), "arg");
+        assert_eq!(
+            load(Some("-".to_string()), env_str(), "stdin", false),
+            "stdin"
+        );
+        assert_eq!(load(None, env_str(), "stdin", true), "env");
+        assert_eq!(load(None, None, "stdin", true), "default");
+        assert_eq!(load(None, None, "stdin", false), "default");
+
+        let missing = env::temp_dir().join("aoc_utils_missing_input.txt");
+        assert_eq!(
+            load_from(None, None, &mut "stdin".as_bytes(), true, &missing).unwrap(),
+            "stdin"
+        );
+    }
+
+    /// Stdin which fails the test if it is read, like a pipe which is never closed
+    struct BlockingStdin;
+
+    impl Read for BlockingStdin {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            panic!("stdin should not be read")
+        }
+    }
+
+    #[test]
+    fn load_from_reads_stdin_last() {
+        let env_path = temp_input("env_only.txt", "env");
+        let default = temp_input("default_only.txt", "default");
+        let missing = env::temp_dir().join("aoc_utils_missing_input.txt");
+        let env_str = Some(env_path.display().to_string());
+        assert_eq!(
+            load_from(None, env_str, &mut BlockingStdin, true, &missing).unwrap(),
+            "env"
+        );
+        assert_eq!(
+            load_from(None, None, &mut BlockingStdin, true, &default).unwrap(),
+            "default"
+        );
+    }
+
+    #[test]
+    fn load_from_reports_searched_places() {
+        let missing = env::temp_dir().join("aoc_utils_missing_input.txt");
+        let err = load_from(None, None, &mut "".as_bytes(), true, &missing).unwrap_err();
+        match &err {
+            LoadInputError::NotFound { searched } => assert_eq!(searched.len(), 4),
+            _ => panic!("Expected NotFound"),
+        }
+        assert!(err.to_string().contains("AOC_INPUT"));
+        assert!(err.to_string().contains("aoc_utils_missing_input.txt"));
+
+        let err = load_from(
+            Some(missing.display().to_string()),
+            None,
+            &mut "".as_bytes(),
+            false,
+            &missing,
+        )
+        .unwrap_err();
+        assert!(matches!(err, LoadInputError::Io { .. }));
+    }
+
+    #[test]
+    fn summarize_empty() {
+        let summary = summarize("");
+        assert_eq!(summary.line_count, 0);
+        assert_eq!((summary.min_width, summary.max_width), (0, 0));
+        assert!(!summary.is_grid);
+        assert_eq!(summary.block_count, 0);
+        assert_eq!(summary.int_range, None);
+    }
+
+    #[test]
+    fn summarize_blocks_and_ints() {
+        let summary = summarize("seeds: 79 14\r\n\r\nmap:\n50 98 2\nx=-3\n");
+        assert_eq!(summary.line_count, 5);
+        assert_eq!((summary.min_width, summary.max_width), (0, 12));
+        assert!(!summary.is_grid);
+        assert_eq!(summary.block_count, 2);
+        assert_eq!(summary.int_range, Some((-3, 98)));
+        assert!(!summary.distinct_chars.contains(&'\r'));
+        assert!(summary.distinct_chars.contains(&' '));
+    }
+
+    #[test]
+    fn summarize_display() {
+        assert_eq!(
+            summarize("ab\nba").to_string(),
+            "lines: 2 (width 2..=2, rectangular grid)\nblocks: 1\ndistinct chars (2): \"ab\"\nintegers: none"
+        );
+    }
 }
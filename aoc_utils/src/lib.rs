@@ -2,9 +2,34 @@
 
 //! Utilities for solving Advent of Code problems in rust.
 
+// Lets code generated by `aoc_utils_derive` refer to `::aoc_utils` from within this crate.
+extern crate self as aoc_utils;
+
 /// Utilities for dealing with input strings.
 pub mod input;
 
+/// Match strings against simple patterns like `"{x},{y} -> {x2},{y2}"`.
+///
+/// With the `derive` feature, `#[derive(FromPattern)]` generates a `FromStr` implementation for
+/// a struct from such a pattern.
+pub mod pattern;
+
+/// Reusable `nom` parser combinators for common puzzle input formats.
+///
+/// Requires the `nom` feature.
+#[cfg(feature = "nom")]
+pub mod parse;
+
+/// Extract example inputs and answers from saved puzzle pages into sample fixture files that
+/// tests can load.
+pub mod sample;
+
+/// Download puzzle inputs from the Advent of Code website and cache them on disk.
+///
+/// Requires the `download` feature.
+#[cfg(feature = "download")]
+pub mod download;
+
 /// It was on the seventh day of Advent of Code, 2022 that Ben learned that
 /// tree structures are hard in Rust. Here are some tools for working with
 /// trees that are ~borrowed~ and adapted from:
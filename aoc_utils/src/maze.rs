@@ -1,6 +1,10 @@
 #![warn(missing_docs)]
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
 use std::hash::Hash;
+use std::ops::{Add, Index, IndexMut};
 
 /// Error type for 2D mazes and associated functions.
 #[derive(Debug)]
@@ -11,8 +15,177 @@ pub enum MazeError {
     LocationDoesNotExist,
 }
 
+/// Error type for parsing a 2D maze from a grid of characters.
+#[derive(Debug, PartialEq)]
+pub enum ParseGridError {
+    /// Raised when a row has a different number of characters than the first row
+    RaggedRow {
+        /// 1-based line number of the offending row
+        line: usize,
+        /// Number of characters in the first row
+        expected: usize,
+        /// Number of characters in the offending row
+        found: usize,
+    },
+    /// Raised when the character mapping does not recognize a character
+    UnknownChar {
+        /// 1-based line number of the character
+        line: usize,
+        /// 1-based column number of the character
+        column: usize,
+        /// The unrecognized character
+        c: char,
+    },
+    /// Raised when a marker character does not appear in the grid
+    MissingMarker(char),
+    /// Raised when a marker character appears in the grid more than once
+    DuplicateMarker(char),
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row on line {} has {} characters, expected {}",
+                line, found, expected
+            ),
+            ParseGridError::UnknownChar { line, column, c } => {
+                write!(f, "unknown character {:?} at {}:{}", c, line, column)
+            }
+            ParseGridError::MissingMarker(c) => write!(f, "marker {:?} not found in grid", c),
+            ParseGridError::DuplicateMarker(c) => {
+                write!(f, "marker {:?} appears in grid more than once", c)
+            }
+        }
+    }
+}
+
+impl Error for ParseGridError {}
+
+/// Orientation of the y axis when parsing a 2D maze from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YAxis {
+    /// The first line of text has `y == 0` and `y` increases going down the text
+    TopDown,
+    /// The last line of text has `y == 0` and `y` increases going up the text, so that
+    /// `NavigableMaze::loc_above` matches the layout of the text
+    BottomUp,
+}
+
+/// Parse a grid of characters into any collection of `((x, y), value)` pairs, such as a
+/// `HashMapMaze`.
+///
+/// Each character is converted with `f`, which returns `None` for characters it doesn't
+/// recognize. The location of each character in `markers` is returned alongside the grid, in
+/// the same order. Every row must have the same number of characters and every marker must
+/// appear exactly once.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let input = "S.#\n..E";
+/// let (maze, [start, end]): (HashMapMaze<_, _>, _) =
+///     parse_char_grid(input, YAxis::TopDown, ['S', 'E'], |c| match c {
+///         '#' => Some(Block::Wall(c)),
+///         '.' | 'S' | 'E' => Some(Block::Path(c)),
+///         _ => None,
+///     })
+///     .unwrap();
+///
+/// assert_eq!((start, end), ((0, 0), (2, 1)));
+/// assert!(matches!(maze.get_value_at_loc(&(2, 0)), Ok(Block::Wall('#'))));
+/// ```
+pub fn parse_char_grid<G, V, F, const N: usize>(
+    input: &str,
+    y_axis: YAxis,
+    markers: [char; N],
+    mut f: F,
+) -> Result<(G, [(usize, usize); N]), ParseGridError>
+where
+    G: FromIterator<((usize, usize), V)>,
+    F: FnMut(char) -> Option<V>,
+{
+    let rows: Vec<&str> = input.lines().collect();
+    let height = rows.len();
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    let mut marker_locs: [Option<(usize, usize)>; N] = [None; N];
+    let mut cells = Vec::with_capacity(width * height);
+    for (row_idx, row) in rows.iter().enumerate() {
+        let y = match y_axis {
+            YAxis::TopDown => row_idx,
+            YAxis::BottomUp => height - 1 - row_idx,
+        };
+        let found = row.chars().count();
+        if found != width {
+            return Err(ParseGridError::RaggedRow {
+                line: row_idx + 1,
+                expected: width,
+                found,
+            });
+        }
+        for (x, c) in row.chars().enumerate() {
+            if let Some(i) = markers.iter().position(|&m| m == c) {
+                if marker_locs[i].replace((x, y)).is_some() {
+                    return Err(ParseGridError::DuplicateMarker(c));
+                }
+            }
+            let value = f(c).ok_or(ParseGridError::UnknownChar {
+                line: row_idx + 1,
+                column: x + 1,
+                c,
+            })?;
+            cells.push(((x, y), value));
+        }
+    }
+    let mut locs = [(0, 0); N];
+    for (i, loc) in marker_locs.into_iter().enumerate() {
+        locs[i] = loc.ok_or(ParseGridError::MissingMarker(markers[i]))?;
+    }
+    Ok((cells.into_iter().collect(), locs))
+}
+
+/// Offsets `(dx, dy)` to the locations above, below, to the left and to the right of a location
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, 1), (0, -1), (-1, 0), (1, 0)];
+
+/// Offsets `(dx, dy)` to the locations diagonally above and below a location
+pub const DIAGONAL: [(isize, isize); 4] = [(-1, 1), (1, 1), (-1, -1), (1, -1)];
+
+/// Offsets `(dx, dy)` to all eight locations around a location, orthogonal ones first
+pub const ORTHOGONAL_AND_DIAGONAL: [(isize, isize); 8] = [
+    (0, 1),
+    (0, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (1, 1),
+    (-1, -1),
+    (1, -1),
+];
+
+/// Offsets `(dx, dy)` to the locations a chess knight can move to from a location
+pub const KNIGHT: [(isize, isize); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
 /// This trait describes a navigable maze, where each generic `Self::Location` type has optionally has a
 /// loc above, below, to the right, and to the left of it.
+///
+/// Every method fails with `MazeError::ImpossibleMove` if the location asked for cannot be
+/// represented, e.g. it is left of zero with unsigned coordinates, and with
+/// `MazeError::LocationDoesNotExist` if it is not in the maze.
 pub trait NavigableMaze {
     /// Represents a location in a 2D maze.
     type Location;
@@ -25,6 +198,122 @@ pub trait NavigableMaze {
     fn loc_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError>;
     /// Find the location in the 2D maze to the left of `loc`
     fn loc_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError>;
+
+    /// Find the location in the 2D maze `dx` to the right of and `dy` above `loc`, where
+    /// negative offsets are to the left and below
+    ///
+    /// The default implementation takes one step at a time, horizontally and then vertically,
+    /// so every location on the way must be in the maze. Mazes which can jump straight to the
+    /// target location should override it.
+    fn loc_offset(
+        &self,
+        mut loc: Self::Location,
+        dx: isize,
+        dy: isize,
+    ) -> Result<Self::Location, MazeError> {
+        for _ in 0..dx.unsigned_abs() {
+            loc = if dx > 0 {
+                self.loc_right(loc)?
+            } else {
+                self.loc_left(loc)?
+            };
+        }
+        for _ in 0..dy.unsigned_abs() {
+            loc = if dy > 0 {
+                self.loc_above(loc)?
+            } else {
+                self.loc_below(loc)?
+            };
+        }
+        Ok(loc)
+    }
+
+    /// Find the location in the 2D maze above and to the left of `loc`
+    fn loc_above_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, -1, 1)
+    }
+    /// Find the location in the 2D maze above and to the right of `loc`
+    fn loc_above_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 1, 1)
+    }
+    /// Find the location in the 2D maze below and to the left of `loc`
+    fn loc_below_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, -1, -1)
+    }
+    /// Find the location in the 2D maze below and to the right of `loc`
+    fn loc_below_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 1, -1)
+    }
+
+    /// Iterate over the locations above, below, to the left and to the right of `loc` which are
+    /// in the maze, in that order
+    fn neighbors4(&self, loc: Self::Location) -> Neighbors<'_, Self>
+    where
+        Self::Location: Copy,
+    {
+        self.neighbors_with(loc, &ORTHOGONAL)
+    }
+
+    /// Iterate over the locations orthogonally and diagonally around `loc` which are in the
+    /// maze, in the order of `ORTHOGONAL_AND_DIAGONAL`
+    fn neighbors8(&self, loc: Self::Location) -> Neighbors<'_, Self>
+    where
+        Self::Location: Copy,
+    {
+        self.neighbors_with(loc, &ORTHOGONAL_AND_DIAGONAL)
+    }
+
+    /// Iterate over the locations at each of `offsets` from `loc` which are in the maze, in the
+    /// order of `offsets`
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::maze::*;
+    ///
+    /// let grid = Grid::new(3, 3, ());
+    ///
+    /// assert_eq!(grid.neighbors_with((0, 0), &KNIGHT).collect::<Vec<_>>(), vec![(1, 2), (2, 1)]);
+    /// assert_eq!(grid.neighbors_with((1, 1), &KNIGHT).count(), 0);
+    /// ```
+    fn neighbors_with<'a>(
+        &'a self,
+        loc: Self::Location,
+        offsets: &'a [(isize, isize)],
+    ) -> Neighbors<'a, Self>
+    where
+        Self::Location: Copy,
+    {
+        Neighbors {
+            maze: self,
+            loc,
+            offsets: offsets.iter(),
+        }
+    }
+}
+
+/// Iterator over the neighbors of a location in a `NavigableMaze` which are in the maze,
+/// returned by `NavigableMaze::neighbors_with` and friends
+pub struct Neighbors<'a, M>
+where
+    M: NavigableMaze + ?Sized,
+{
+    maze: &'a M,
+    loc: M::Location,
+    offsets: std::slice::Iter<'a, (isize, isize)>,
+}
+
+impl<M> Iterator for Neighbors<'_, M>
+where
+    M: NavigableMaze + ?Sized,
+    M::Location: Copy,
+{
+    type Item = M::Location;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.offsets
+            .by_ref()
+            .find_map(|&(dx, dy)| self.maze.loc_offset(self.loc, dx, dy).ok())
+    }
 }
 
 /// This supertrait is a describes a `NavigableMaze` with state representing the current location
@@ -40,6 +329,15 @@ pub trait SingleOccupantMaze: NavigableMaze {
     fn move_left(&mut self) -> Result<(), MazeError>;
 }
 
+/// This supertrait describes a `NavigableMaze` which holds a value at each of its locations.
+pub trait ValuedMaze: NavigableMaze {
+    /// Type of the value held at each location
+    type Value;
+
+    /// Get the value of the maze at `loc`
+    fn get_value_at_loc(&self, loc: &Self::Location) -> Result<&Self::Value, MazeError>;
+}
+
 /// A 2D maze where each location and value in the maze is stored in a `HashMap`. The idea here is
 /// that each key in the hashmap uniquely identifies a location in a 2D grid. Therefore, a natural
 /// choice for the generic type `L` is `(usize, usize)`
@@ -77,13 +375,90 @@ where
 
     /// Get the value of the maze at `loc`
     pub fn get_value_at_loc(&self, loc: &L) -> Result<&V, MazeError> {
-        match self.map.get(&loc) {
+        match self.map.get(loc) {
             None => Err(MazeError::LocationDoesNotExist),
             Some(value) => Ok(value),
         }
     }
 }
 
+impl<L, V> Default for HashMapMaze<L, V>
+where
+    L: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L, V> FromIterator<(L, V)> for HashMapMaze<L, V>
+where
+    L: Hash + Eq,
+{
+    fn from_iter<I: IntoIterator<Item = (L, V)>>(iter: I) -> Self {
+        let map = iter.into_iter().collect();
+        Self { map }
+    }
+}
+
+/// A maze is serialized as a sequence of `(location, value)` pairs rather than as a map, since
+/// formats like JSON only allow strings as map keys.
+#[cfg(feature = "serde")]
+impl<L, V> serde::Serialize for HashMapMaze<L, V>
+where
+    L: Hash + Eq + serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.map)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L, V> serde::Deserialize<'de> for HashMapMaze<L, V>
+where
+    L: Hash + Eq + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let locs: Vec<(L, V)> = Vec::deserialize(deserializer)?;
+        Ok(locs.into_iter().collect())
+    }
+}
+
+impl<V> HashMapMaze<(usize, usize), V> {
+    /// Create a `HashMapMaze` from a grid of characters, converting each character with `f`.
+    ///
+    /// See `parse_char_grid` for details of how the text is parsed.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::maze::*;
+    ///
+    /// let (maze, [start]) =
+    ///     HashMapMaze::from_char_grid("Sab\nbcd", YAxis::BottomUp, ['S'], |c| match c {
+    ///         'S' => Some(0),
+    ///         'a'..='z' => Some(c as u8 - b'a'),
+    ///         _ => None,
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(start, (0, 1));
+    /// assert_eq!(maze.get_value_at_loc(&maze.loc_below(start).unwrap()).unwrap(), &1);
+    /// ```
+    pub fn from_char_grid<F, const N: usize>(
+        input: &str,
+        y_axis: YAxis,
+        markers: [char; N],
+        f: F,
+    ) -> Result<(Self, [(usize, usize); N]), ParseGridError>
+    where
+        F: FnMut(char) -> Option<V>,
+    {
+        parse_char_grid(input, y_axis, markers, f)
+    }
+}
+
 impl<L, V> HashMapOccupiedMaze<L, V>
 where
     L: Hash + Eq,
@@ -115,77 +490,185 @@ where
     }
 }
 
-impl<V> NavigableMaze for HashMapMaze<(usize, usize), V> {
-    /// Represents a location in a 2D maze.
-    type Location = (usize, usize);
+#[cfg(feature = "serde")]
+impl<L, V> serde::Serialize for HashMapOccupiedMaze<L, V>
+where
+    L: Hash + Eq + serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
 
-    /// Find the location in the 2D maze above `loc`
-    fn loc_above(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        let other_loc = (loc.0, loc.1 + 1);
-        if self.map.contains_key(&other_loc) {
-            Ok(other_loc)
+        let mut state = serializer.serialize_struct("HashMapOccupiedMaze", 2)?;
+        state.serialize_field("maze", &self.maze)?;
+        state.serialize_field("loc", &self.loc)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "HashMapOccupiedMaze")]
+struct SerializedOccupiedMaze<L, V>
+where
+    L: Hash + Eq,
+{
+    maze: HashMapMaze<L, V>,
+    loc: L,
+}
+
+/// Will return error if the occupant is not at a location in the maze, as with
+/// `HashMapOccupiedMaze::from_hash_map_maze`.
+#[cfg(feature = "serde")]
+impl<'de, L, V> serde::Deserialize<'de> for HashMapOccupiedMaze<L, V>
+where
+    L: Hash + Eq + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SerializedOccupiedMaze { maze, loc } =
+            SerializedOccupiedMaze::deserialize(deserializer)?;
+        HashMapOccupiedMaze::from_hash_map_maze(maze, loc)
+            .map_err(|_| serde::de::Error::custom("occupant is not at a location in the maze"))
+    }
+}
+
+/// An integer type which can be used for each coordinate of a location `(x, y)` in a 2D maze.
+///
+/// Implemented for all the primitive integer types. Signed coordinates let a maze extend in
+/// every direction from the origin, while with unsigned coordinates nothing is below or to the
+/// left of zero.
+pub trait Coordinate: Copy + Eq + Hash {
+    /// Get the coordinate `delta` from this one, or `None` if it would overflow
+    fn offset(self, delta: isize) -> Option<Self>;
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),* => |$c:ident, $d:ident| $offset:expr) => {
+        $(
+            impl Coordinate for $t {
+                fn offset(self, delta: isize) -> Option<Self> {
+                    let ($c, $d) = (self, delta as i128);
+                    $offset
+                }
+            }
+        )*
+    };
+}
+
+// Every other type fits in an i128, so the offset can be added without overflowing
+impl_coordinate!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize => |c, d| Self::try_from(c as i128 + d).ok());
+impl_coordinate!(u128 => |c, d| c.checked_add_signed(d));
+impl_coordinate!(i128 => |c, d| c.checked_add(d));
+
+impl<C, V> HashMapMaze<(C, C), V>
+where
+    C: Coordinate,
+{
+    /// Return `loc` if it is in the maze. `loc` is `None` if the move to it would take a
+    /// coordinate out of the range of its type.
+    fn existing_loc(&self, loc: Option<(C, C)>) -> Result<(C, C), MazeError> {
+        let loc = loc.ok_or(MazeError::ImpossibleMove)?;
+        if self.map.contains_key(&loc) {
+            Ok(loc)
         } else {
             Err(MazeError::LocationDoesNotExist)
         }
     }
+}
+
+impl<C, V> NavigableMaze for HashMapMaze<(C, C), V>
+where
+    C: Coordinate,
+{
+    /// Represents a location in a 2D maze.
+    type Location = (C, C);
 
+    /// Find the location in the 2D maze above `loc`
+    fn loc_above(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 0, 1)
+    }
     /// Find the location in the 2D maze below `loc`
     fn loc_below(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        if loc.1 == 0 {
-            return Err(MazeError::ImpossibleMove);
-        }
-        let other_loc = (loc.0, loc.1 - 1);
-        if self.map.contains_key(&other_loc) {
-            Ok(other_loc)
-        } else {
-            Err(MazeError::LocationDoesNotExist)
-        }
+        self.loc_offset(loc, 0, -1)
     }
     /// Find the location in the 2D maze to the right of `loc`
     fn loc_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        let other_loc = (loc.0 + 1, loc.1);
-        if self.map.contains_key(&other_loc) {
-            Ok(other_loc)
-        } else {
-            Err(MazeError::LocationDoesNotExist)
-        }
+        self.loc_offset(loc, 1, 0)
     }
     /// Find the location in the 2D maze to the left of `loc`
     fn loc_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        if loc.0 == 0 {
-            return Err(MazeError::ImpossibleMove);
-        }
-        let other_loc = (loc.0 - 1, loc.1);
-        if self.map.contains_key(&other_loc) {
-            Ok(other_loc)
-        } else {
-            Err(MazeError::LocationDoesNotExist)
-        }
+        self.loc_offset(loc, -1, 0)
+    }
+
+    fn loc_offset(
+        &self,
+        loc: Self::Location,
+        dx: isize,
+        dy: isize,
+    ) -> Result<Self::Location, MazeError> {
+        self.existing_loc(loc.0.offset(dx).zip(loc.1.offset(dy)))
     }
 }
 
-impl<V> NavigableMaze for HashMapOccupiedMaze<(usize, usize), V> {
-    type Location = (usize, usize);
+impl<C, V> NavigableMaze for HashMapOccupiedMaze<(C, C), V>
+where
+    C: Coordinate,
+{
+    type Location = (C, C);
+
     /// Find the location in the 2D maze above `loc`
     fn loc_above(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        self.maze.loc_above(loc)
+        self.loc_offset(loc, 0, 1)
     }
     /// Find the location in the 2D maze below `loc`
     fn loc_below(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        self.maze.loc_below(loc)
+        self.loc_offset(loc, 0, -1)
     }
     /// Find the location in the 2D maze to the right of `loc`
     fn loc_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        self.maze.loc_right(loc)
+        self.loc_offset(loc, 1, 0)
     }
     /// Find the location in the 2D maze to the left of `loc`
     fn loc_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
-        self.maze.loc_left(loc)
+        self.loc_offset(loc, -1, 0)
+    }
+
+    fn loc_offset(
+        &self,
+        loc: Self::Location,
+        dx: isize,
+        dy: isize,
+    ) -> Result<Self::Location, MazeError> {
+        self.maze.loc_offset(loc, dx, dy)
+    }
+}
+
+impl<C, V> ValuedMaze for HashMapMaze<(C, C), V>
+where
+    C: Coordinate,
+{
+    type Value = V;
+
+    fn get_value_at_loc(&self, loc: &Self::Location) -> Result<&V, MazeError> {
+        HashMapMaze::get_value_at_loc(self, loc)
+    }
+}
+
+impl<C, V> ValuedMaze for HashMapOccupiedMaze<(C, C), V>
+where
+    C: Coordinate,
+{
+    type Value = V;
+
+    fn get_value_at_loc(&self, loc: &Self::Location) -> Result<&V, MazeError> {
+        self.maze.get_value_at_loc(loc)
     }
 }
 
 /// Enum for defining locations in a maze which are accessible or inaccessible.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Block<P, W> {
     /// An accessible location containing an object of type `P`
     Path(P),
@@ -193,7 +676,10 @@ pub enum Block<P, W> {
     Wall(W),
 }
 
-impl<P, W> SingleOccupantMaze for HashMapOccupiedMaze<(usize, usize), Block<P, W>> {
+impl<C, P, W> SingleOccupantMaze for HashMapOccupiedMaze<(C, C), Block<P, W>>
+where
+    C: Coordinate,
+{
     fn move_up(&mut self) -> Result<(), MazeError> {
         let loc = self.loc_above(self.loc)?;
         match self.maze.get_value_at_loc(&loc)? {
@@ -236,81 +722,1693 @@ impl<P, W> SingleOccupantMaze for HashMapOccupiedMaze<(usize, usize), Block<P, W
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A dense 2D maze storing the value at every location `(x, y)` with `x < width` and
+/// `y < height` in a row-major `Vec`.
+///
+/// Unlike `HashMapMaze`, looking up a location is plain indexing rather than hashing, which
+/// makes it the better choice for rectangular puzzle inputs where every location has a value.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let (mut grid, [start]) =
+///     Grid::from_char_grid("S12\n345", YAxis::TopDown, ['S'], |c| c.to_digit(10).or(Some(0)))
+///         .unwrap();
+///
+/// assert_eq!((grid.width(), grid.height()), (3, 2));
+/// assert_eq!(grid[grid.loc_below((1, 1)).unwrap()], 1);
+/// grid[start] = 9;
+/// assert_eq!(grid.rows().next().unwrap(), &[9, 1, 2]);
+/// assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 5]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Grid<V> {
+    width: usize,
+    height: usize,
+    cells: Vec<V>,
+}
 
-    #[test]
-    fn hash_map_occupied_maze_basic() {
-        let mut maze = HashMapMaze::new();
-        maze.add_loc((0, 0), "start");
-        let mut maze = HashMapOccupiedMaze::from_hash_map_maze(maze, (0, 0)).unwrap();
-        maze.add_loc((1, 0), "one_right");
-        assert_eq!(maze.get_value(), &"start");
-        assert_eq!(
-            maze.get_value_at_loc(&maze.loc_right(maze.loc).unwrap())
-                .unwrap(),
-            &"one_right"
-        );
+/// Wraps a `Grid` with a current location to enable a set of associated functions for mutably
+/// moving around in it, like `HashMapOccupiedMaze` does for a `HashMapMaze`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OccupiedGrid<V> {
+    grid: Grid<V>,
+    loc: (usize, usize),
+}
+
+impl<V> Grid<V> {
+    /// Create a `Grid` of the given size with every location set to `value`.
+    pub fn new(width: usize, height: usize, value: V) -> Self
+    where
+        V: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
     }
 
-    #[test]
-    fn hash_map_maze_impl_navigable_maze() {
-        let mut maze = HashMapMaze::new();
-        for i in 0..10 {
-            for j in 0..10 {
-                maze.add_loc((i, j), ".")
-            }
+    /// Create a `Grid` of the given size with the value at each location given by `f`.
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut((usize, usize)) -> V,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
         }
-        assert_eq!(maze.loc_above((5, 5)).unwrap(), (5, 6));
-        assert_eq!(maze.loc_below((5, 5)).unwrap(), (5, 4));
-        assert_eq!(maze.loc_right((5, 5)).unwrap(), (6, 5));
-        assert_eq!(maze.loc_left((5, 5)).unwrap(), (4, 5));
     }
 
-    #[test]
-    fn hash_map_maze_impl_navigable_maze_panics() {
-        let mut maze = HashMapMaze::new();
-        for i in 0..10 {
-            for j in 0..10 {
-                maze.add_loc((i, j), ".")
-            }
+    /// Create a `Grid` from a grid of characters, converting each character with `f`.
+    ///
+    /// See `parse_char_grid` for details of how the text is parsed.
+    pub fn from_char_grid<F, const N: usize>(
+        input: &str,
+        y_axis: YAxis,
+        markers: [char; N],
+        f: F,
+    ) -> Result<(Self, [(usize, usize); N]), ParseGridError>
+    where
+        F: FnMut(char) -> Option<V>,
+    {
+        let (cells, locs): (Vec<((usize, usize), V)>, _) =
+            parse_char_grid(input, y_axis, markers, f)?;
+        let width = cells.iter().map(|((x, _), _)| x + 1).max().unwrap_or(0);
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        let mut slots: Vec<Option<V>> = cells.iter().map(|_| None).collect();
+        for ((x, y), value) in cells {
+            slots[y * width + x] = Some(value);
         }
-        assert_eq!(maze.loc_below((0, 0)).unwrap_or((666, 666)), (666, 666));
-        assert_eq!(maze.loc_left((0, 0)).unwrap_or((666, 666)), (666, 666));
-        assert_eq!(maze.loc_above((9, 9)).unwrap_or((666, 666)), (666, 666));
-        assert_eq!(maze.loc_right((9, 9)).unwrap_or((666, 666)), (666, 666));
+        let cells = slots
+            .into_iter()
+            .map(|value| value.expect("Grid parsed from text is rectangular"))
+            .collect();
+        Ok((
+            Self {
+                width,
+                height,
+                cells,
+            },
+            locs,
+        ))
     }
 
-    #[test]
-    fn hash_map_occupied_maze_traverse_chars() {
-        let mut maze = HashMapMaze::new();
-        maze.add_loc((0, 0), Block::Path('.'));
-        maze.add_loc((0, 1), Block::Path('.'));
-        maze.add_loc((0, 2), Block::Path('.'));
-        maze.add_loc((1, 0), Block::Path('.'));
-        maze.add_loc((1, 1), Block::Wall('x'));
-        maze.add_loc((1, 2), Block::Path('.'));
-        maze.add_loc((2, 0), Block::Path('.'));
-        maze.add_loc((2, 1), Block::Path('.'));
-        maze.add_loc((2, 2), Block::Path('.'));
-        let mut maze = HashMapOccupiedMaze::from_hash_map_maze(maze, (0, 0)).unwrap();
-        maze.move_up().unwrap();
-        maze.move_up().unwrap();
-        assert_eq!(maze.loc, (0, 2));
-        maze.move_right().unwrap();
-        maze.move_right().unwrap();
-        assert_eq!(maze.loc, (2, 2));
-        maze.move_down().unwrap();
-        maze.move_down().unwrap();
-        assert_eq!(maze.loc, (2, 0));
-        maze.move_left().unwrap();
-        assert_eq!(maze.loc, (1, 0));
-        let mut move_failed = false;
-        maze.move_up().unwrap_or_else(|_| {
-            move_failed = true;
-            ()
-        });
-        assert!(move_failed)
+    /// Get the number of locations in each row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of locations in each column.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, loc: (usize, usize)) -> Option<usize> {
+        if loc.0 < self.width && loc.1 < self.height {
+            Some(loc.1 * self.width + loc.0)
+        } else {
+            None
+        }
+    }
+
+    /// Return `loc` if it is in the grid. `loc` is `None` if the move to it would take a
+    /// coordinate out of the range of `usize`.
+    fn existing_loc(&self, loc: Option<(usize, usize)>) -> Result<(usize, usize), MazeError> {
+        let loc = loc.ok_or(MazeError::ImpossibleMove)?;
+        if self.contains_loc(&loc) {
+            Ok(loc)
+        } else {
+            Err(MazeError::LocationDoesNotExist)
+        }
+    }
+
+    /// Check whether `loc` is inside the grid.
+    pub fn contains_loc(&self, loc: &(usize, usize)) -> bool {
+        self.index_of(*loc).is_some()
+    }
+
+    /// Get the value of the grid at `loc`
+    pub fn get_value_at_loc(&self, loc: &(usize, usize)) -> Result<&V, MazeError> {
+        match self.index_of(*loc) {
+            None => Err(MazeError::LocationDoesNotExist),
+            Some(i) => Ok(&self.cells[i]),
+        }
+    }
+
+    /// Get a mutable reference to the value of the grid at `loc`
+    pub fn get_value_at_loc_mut(&mut self, loc: &(usize, usize)) -> Result<&mut V, MazeError> {
+        match self.index_of(*loc) {
+            None => Err(MazeError::LocationDoesNotExist),
+            Some(i) => Ok(&mut self.cells[i]),
+        }
+    }
+
+    /// Iterate over every location in the grid and its value, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &V)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| ((i % width, i / width), value))
+    }
+
+    /// Get the values of row `y`, in order of increasing `x`.
+    ///
+    /// Will return None if the row is outside the grid.
+    pub fn row(&self, y: usize) -> Option<&[V]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the rows of the grid, in order of increasing `y`.
+    pub fn rows(&self) -> impl Iterator<Item = &[V]> {
+        // chunks panics on a chunk size of 0, and a grid with no columns has no values anyway
+        self.cells
+            .chunks(self.width.max(1))
+            .take(self.height * usize::from(self.width > 0))
+    }
+
+    /// Iterate over the values of column `x`, in order of increasing `y`.
+    ///
+    /// The iterator is empty if the column is outside the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &V> {
+        let start = if x < self.width { x } else { self.cells.len() };
+        self.cells[start..].iter().step_by(self.width.max(1))
+    }
+
+    /// Iterate over the columns of the grid, in order of increasing `x`.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &V>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+}
+
+/// Get the value at a location, panicking if it is outside the grid
+impl<V> Index<(usize, usize)> for Grid<V> {
+    type Output = V;
+
+    fn index(&self, loc: (usize, usize)) -> &V {
+        match self.index_of(loc) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "location {:?} is outside a grid of size {}x{}",
+                loc, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<V> IndexMut<(usize, usize)> for Grid<V> {
+    fn index_mut(&mut self, loc: (usize, usize)) -> &mut V {
+        match self.index_of(loc) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "location {:?} is outside a grid of size {}x{}",
+                loc, self.width, self.height
+            ),
+        }
+    }
+}
+
+/// Convert a `HashMapMaze` which has a value at every location of a rectangle with one corner at
+/// `(0, 0)` into a `Grid`.
+///
+/// Will return error if any location in that rectangle is missing.
+impl<V> TryFrom<HashMapMaze<(usize, usize), V>> for Grid<V> {
+    type Error = MazeError;
+
+    fn try_from(maze: HashMapMaze<(usize, usize), V>) -> Result<Self, MazeError> {
+        let (mut width, mut height) = (0, 0);
+        for &(x, y) in maze.map.keys() {
+            let overflow = || MazeError::LocationDoesNotExist;
+            width = width.max(x.checked_add(1).ok_or_else(overflow)?);
+            height = height.max(y.checked_add(1).ok_or_else(overflow)?);
+        }
+        if width.checked_mul(height) != Some(maze.map.len()) {
+            return Err(MazeError::LocationDoesNotExist);
+        }
+        let mut slots: Vec<Option<V>> = maze.map.values().map(|_| None).collect();
+        for ((x, y), value) in maze.map {
+            slots[y * width + x] = Some(value);
+        }
+        let cells = slots
+            .into_iter()
+            .map(|value| value.expect("Every location in the rectangle was filled"))
+            .collect();
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+}
+
+impl<V> From<Grid<V>> for HashMapMaze<(usize, usize), V> {
+    fn from(grid: Grid<V>) -> Self {
+        let width = grid.width;
+        grid.cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| ((i % width, i / width), value))
+            .collect()
+    }
+}
+
+/// Will return error if the width and height do not match the number of values.
+#[cfg(feature = "serde")]
+impl<'de, V> serde::Deserialize<'de> for Grid<V>
+where
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Grid")]
+        struct SerializedGrid<V> {
+            width: usize,
+            height: usize,
+            cells: Vec<V>,
+        }
+
+        let SerializedGrid {
+            width,
+            height,
+            cells,
+        } = SerializedGrid::deserialize(deserializer)?;
+        if width.checked_mul(height) != Some(cells.len()) {
+            return Err(serde::de::Error::custom(format!(
+                "grid of size {}x{} cannot hold {} values",
+                width,
+                height,
+                cells.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+}
+
+/// Will return error if the occupant is not at a location in the grid, as with
+/// `OccupiedGrid::from_grid`.
+#[cfg(feature = "serde")]
+impl<'de, V> serde::Deserialize<'de> for OccupiedGrid<V>
+where
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "OccupiedGrid")]
+        struct SerializedOccupiedGrid<V> {
+            grid: Grid<V>,
+            loc: (usize, usize),
+        }
+
+        let SerializedOccupiedGrid { grid, loc } =
+            SerializedOccupiedGrid::deserialize(deserializer)?;
+        OccupiedGrid::from_grid(grid, loc)
+            .map_err(|_| serde::de::Error::custom("occupant is not at a location in the grid"))
+    }
+}
+
+impl<V> NavigableMaze for Grid<V> {
+    /// Represents a location in a 2D maze.
+    type Location = (usize, usize);
+
+    /// Find the location in the 2D maze above `loc`
+    fn loc_above(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 0, 1)
+    }
+    /// Find the location in the 2D maze below `loc`
+    fn loc_below(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 0, -1)
+    }
+    /// Find the location in the 2D maze to the right of `loc`
+    fn loc_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 1, 0)
+    }
+    /// Find the location in the 2D maze to the left of `loc`
+    fn loc_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, -1, 0)
+    }
+
+    fn loc_offset(
+        &self,
+        loc: Self::Location,
+        dx: isize,
+        dy: isize,
+    ) -> Result<Self::Location, MazeError> {
+        self.existing_loc(loc.0.offset(dx).zip(loc.1.offset(dy)))
+    }
+}
+
+impl<V> OccupiedGrid<V> {
+    /// Create an `OccupiedGrid` from a `Grid` by providing the location of the occupant.
+    pub fn from_grid(grid: Grid<V>, loc: (usize, usize)) -> Result<Self, MazeError> {
+        if grid.contains_loc(&loc) {
+            Ok(Self { grid, loc })
+        } else {
+            Err(MazeError::LocationDoesNotExist)
+        }
+    }
+
+    /// Get the location of the occupant.
+    pub fn loc(&self) -> (usize, usize) {
+        self.loc
+    }
+
+    /// Get the value of the grid at the current location.
+    pub fn get_value(&self) -> &V {
+        &self.grid[self.loc]
+    }
+
+    /// Get the value of the grid at `loc`
+    pub fn get_value_at_loc(&self, loc: &(usize, usize)) -> Result<&V, MazeError> {
+        self.grid.get_value_at_loc(loc)
+    }
+
+    /// Get the underlying grid.
+    pub fn grid(&self) -> &Grid<V> {
+        &self.grid
+    }
+}
+
+impl<V> NavigableMaze for OccupiedGrid<V> {
+    type Location = (usize, usize);
+
+    /// Find the location in the 2D maze above `loc`
+    fn loc_above(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 0, 1)
+    }
+    /// Find the location in the 2D maze below `loc`
+    fn loc_below(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 0, -1)
+    }
+    /// Find the location in the 2D maze to the right of `loc`
+    fn loc_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, 1, 0)
+    }
+    /// Find the location in the 2D maze to the left of `loc`
+    fn loc_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+        self.loc_offset(loc, -1, 0)
+    }
+
+    fn loc_offset(
+        &self,
+        loc: Self::Location,
+        dx: isize,
+        dy: isize,
+    ) -> Result<Self::Location, MazeError> {
+        self.grid.loc_offset(loc, dx, dy)
+    }
+}
+
+impl<V> ValuedMaze for Grid<V> {
+    type Value = V;
+
+    fn get_value_at_loc(&self, loc: &Self::Location) -> Result<&V, MazeError> {
+        Grid::get_value_at_loc(self, loc)
+    }
+}
+
+impl<V> ValuedMaze for OccupiedGrid<V> {
+    type Value = V;
+
+    fn get_value_at_loc(&self, loc: &Self::Location) -> Result<&V, MazeError> {
+        self.grid.get_value_at_loc(loc)
+    }
+}
+
+impl<P, W> OccupiedGrid<Block<P, W>> {
+    fn move_to(&mut self, loc: Result<(usize, usize), MazeError>) -> Result<(), MazeError> {
+        let loc = loc?;
+        match self.grid[loc] {
+            Block::Path(_) => {
+                self.loc = loc;
+                Ok(())
+            }
+            Block::Wall(_) => Err(MazeError::ImpossibleMove),
+        }
+    }
+}
+
+impl<P, W> SingleOccupantMaze for OccupiedGrid<Block<P, W>> {
+    fn move_up(&mut self) -> Result<(), MazeError> {
+        self.move_to(self.loc_above(self.loc))
+    }
+    fn move_down(&mut self) -> Result<(), MazeError> {
+        self.move_to(self.loc_below(self.loc))
+    }
+    fn move_right(&mut self) -> Result<(), MazeError> {
+        self.move_to(self.loc_right(self.loc))
+    }
+    fn move_left(&mut self) -> Result<(), MazeError> {
+        self.move_to(self.loc_left(self.loc))
+    }
+}
+
+/// The cheapest path to a goal found by `bfs`, `dijkstra`, `astar` or `nearest`.
+#[derive(Debug, Clone)]
+pub struct ShortestPath<L, C> {
+    /// Total cost of the path, which for `bfs` and `nearest` is its number of steps
+    pub cost: C,
+    /// Location the path ends at
+    pub goal: L,
+    predecessors: HashMap<L, L>,
+}
+
+impl<L, C> ShortestPath<L, C>
+where
+    L: Copy + Eq + Hash,
+{
+    /// Get the locations along the path, from the start to the goal inclusive.
+    pub fn path(&self) -> Vec<L> {
+        trace_back(&self.predecessors, self.goal)
+    }
+
+    /// Get the location each location reached by the search was first reached from on its
+    /// cheapest path. The start has no predecessor.
+    pub fn predecessors(&self) -> &HashMap<L, L> {
+        &self.predecessors
+    }
+}
+
+/// The cost of the cheapest path from the nearest of a set of sources to every location which
+/// can be reached from them, as found by `bfs_distances` or `dijkstra_distances`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let (grid, [a, b]) = Grid::from_char_grid("a...b", YAxis::TopDown, ['a', 'b'], Some).unwrap();
+/// let field = bfs_distances(&grid, [a, b], |_, _| true);
+///
+/// assert_eq!(field.cost(&(1, 0)), Some(1));
+/// assert_eq!(field.cost(&(3, 0)), Some(1));
+/// assert_eq!(field.path_to((3, 0)), Some(vec![(4, 0), (3, 0)]));
+/// assert_eq!(field.nearest(|&loc| loc.0 == 2), Some(((2, 0), 2)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DistanceField<L, C> {
+    costs: HashMap<L, C>,
+    predecessors: HashMap<L, L>,
+}
+
+impl<L, C> DistanceField<L, C>
+where
+    L: Copy + Eq + Hash,
+    C: Copy + Ord,
+{
+    /// Get the cost of the cheapest path from any source to `loc`.
+    ///
+    /// Will return None if `loc` cannot be reached.
+    pub fn cost(&self, loc: &L) -> Option<C> {
+        self.costs.get(loc).copied()
+    }
+
+    /// Get the locations along the cheapest path to `loc`, from its nearest source to `loc`
+    /// inclusive.
+    ///
+    /// Will return None if `loc` cannot be reached.
+    pub fn path_to(&self, loc: L) -> Option<Vec<L>> {
+        if self.costs.contains_key(&loc) {
+            Some(trace_back(&self.predecessors, loc))
+        } else {
+            None
+        }
+    }
+
+    /// Find the reachable location with the cheapest path for which `matches` returns true,
+    /// along with the cost of that path. If several are equally cheap, any one of them may be
+    /// returned.
+    ///
+    /// Will return None if no matching location can be reached.
+    pub fn nearest<P>(&self, mut matches: P) -> Option<(L, C)>
+    where
+        P: FnMut(&L) -> bool,
+    {
+        self.costs
+            .iter()
+            .filter(|(loc, _)| matches(loc))
+            .min_by_key(|(_, &cost)| cost)
+            .map(|(&loc, &cost)| (loc, cost))
+    }
+
+    /// Iterate over every reachable location and the cost of the cheapest path to it, in no
+    /// particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &C)> {
+        self.costs.iter()
+    }
+
+    /// Get the number of reachable locations, including the sources.
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Check whether no locations can be reached, which is only the case if none of the
+    /// sources are in the maze.
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// Get the location each reachable location was first reached from on its cheapest path.
+    /// The sources have no predecessor.
+    pub fn predecessors(&self) -> &HashMap<L, L> {
+        &self.predecessors
+    }
+}
+
+/// Follow predecessors back from `loc` to a location with none, and return the locations
+/// visited in order from that location to `loc`.
+fn trace_back<L>(predecessors: &HashMap<L, L>, loc: L) -> Vec<L>
+where
+    L: Copy + Eq + Hash,
+{
+    let mut path = vec![loc];
+    let mut loc = loc;
+    while let Some(&previous) = predecessors.get(&loc) {
+        path.push(previous);
+        loc = previous;
+    }
+    path.reverse();
+    path
+}
+
+/// Swap the arguments of a move-allowed predicate or cost closure, to search a maze backwards
+/// from where paths end.
+///
+/// A step from `to` back to `from` is then allowed, and costs the same, exactly when the step
+/// from `from` to `to` was.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let grid = Grid::from_fn(5, 1, |(x, _)| [0, 2, 3, 4, 5][x]);
+/// let climbable = |&from: &u8, &to: &u8| to <= from + 1;
+///
+/// // The top can be reached from everywhere but the bottom, which is two steps below the rest
+/// let field = bfs_distances(&grid, [(4, 0)], reversed(climbable));
+/// assert_eq!(field.len(), 4);
+/// // while any step down is allowed, so everywhere can be reached from the top
+/// let field = bfs_distances(&grid, [(4, 0)], climbable);
+/// assert_eq!(field.len(), 5);
+/// ```
+pub fn reversed<V, R, F>(mut f: F) -> impl FnMut(&V, &V) -> R
+where
+    F: FnMut(&V, &V) -> R,
+{
+    move |from, to| f(to, from)
+}
+
+/// Locations reached by a search and the cost of the cheapest path found to each, the
+/// predecessor of each on that path, and the goal the search stopped at if any.
+type Searched<L, C> = (HashMap<L, C>, HashMap<L, L>, Option<L>);
+
+/// Breadth-first search outwards from every source at once until a location for which
+/// `is_goal` returns true is reached. Sources which are not in the maze are ignored.
+fn search_steps<M, G, A>(
+    maze: &M,
+    sources: impl IntoIterator<Item = M::Location>,
+    mut is_goal: G,
+    mut allowed: A,
+) -> Searched<M::Location, usize>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    G: FnMut(&M::Location) -> bool,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+{
+    let mut steps = HashMap::new();
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if maze.get_value_at_loc(&source).is_ok() && !steps.contains_key(&source) {
+            steps.insert(source, 0);
+            queue.push_back(source);
+        }
+    }
+    let mut predecessors = HashMap::new();
+    while let Some(loc) = queue.pop_front() {
+        if is_goal(&loc) {
+            return (steps, predecessors, Some(loc));
+        }
+        let n = steps[&loc];
+        let value = maze.get_value_at_loc(&loc).expect("Location was reached");
+        for next in maze.neighbors4(loc) {
+            if steps.contains_key(&next) {
+                continue;
+            }
+            let Ok(next_value) = maze.get_value_at_loc(&next) else {
+                continue;
+            };
+            if allowed(value, next_value) {
+                steps.insert(next, n + 1);
+                predecessors.insert(next, loc);
+                queue.push_back(next);
+            }
+        }
+    }
+    (steps, predecessors, None)
+}
+
+/// A* search outwards from every source at once until a location for which `is_goal` returns
+/// true is reached, which with a heuristic of zero is Dijkstra's algorithm. Sources which are
+/// not in the maze are ignored.
+fn search_costs<M, G, A, K, H, C>(
+    maze: &M,
+    sources: impl IntoIterator<Item = M::Location>,
+    mut is_goal: G,
+    mut allowed: A,
+    mut cost: K,
+    mut heuristic: H,
+) -> Searched<M::Location, C>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    G: FnMut(&M::Location) -> bool,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+    K: FnMut(&M::Value, &M::Value) -> C,
+    H: FnMut(&M::Location) -> C,
+    C: Copy + Ord + Add<Output = C> + Default,
+{
+    let mut costs = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for source in sources {
+        if maze.get_value_at_loc(&source).is_ok() && !costs.contains_key(&source) {
+            costs.insert(source, C::default());
+            queue.push(Queued {
+                priority: heuristic(&source),
+                cost: C::default(),
+                loc: source,
+            });
+        }
+    }
+    let mut predecessors = HashMap::new();
+    while let Some(Queued { cost: c, loc, .. }) = queue.pop() {
+        if costs[&loc] < c {
+            // A cheaper way to this location was already found after this one was queued
+            continue;
+        }
+        if is_goal(&loc) {
+            return (costs, predecessors, Some(loc));
+        }
+        let value = maze.get_value_at_loc(&loc).expect("Location was reached");
+        for next in maze.neighbors4(loc) {
+            let Ok(next_value) = maze.get_value_at_loc(&next) else {
+                continue;
+            };
+            if !allowed(value, next_value) {
+                continue;
+            }
+            let next_cost = c + cost(value, next_value);
+            if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            costs.insert(next, next_cost);
+            predecessors.insert(next, loc);
+            queue.push(Queued {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                loc: next,
+            });
+        }
+    }
+    (costs, predecessors, None)
+}
+
+/// Find the path with the fewest steps from `start` to the nearest location for which `is_goal`
+/// returns true, with a breadth-first search.
+///
+/// A step from one location to its neighbor is only taken if `allowed` returns true for the
+/// values at the two locations. Will return None if no goal can be reached.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let (grid, [start, end]) =
+///     Grid::from_char_grid("S#.\n..E", YAxis::TopDown, ['S', 'E'], Some).unwrap();
+///
+/// let path = bfs(&grid, start, |&loc| loc == end, |_, &to| to != '#').unwrap();
+///
+/// assert_eq!(path.cost, 3);
+/// assert_eq!(path.path(), vec![(0, 0), (0, 1), (1, 1), (2, 1)]);
+/// ```
+pub fn bfs<M, G, A>(
+    maze: &M,
+    start: M::Location,
+    is_goal: G,
+    allowed: A,
+) -> Option<ShortestPath<M::Location, usize>>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    G: FnMut(&M::Location) -> bool,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+{
+    nearest(maze, [start], is_goal, allowed)
+}
+
+/// Find the path with the fewest steps from any of `sources` to the nearest location for which
+/// `matches` returns true, with a breadth-first search that stops as soon as one is reached.
+///
+/// Takes the same arguments as `bfs`, apart from the set of sources. To find the nearest source
+/// to a single goal instead, search backwards from the goal with `reversed(allowed)`. Will
+/// return None if no matching location can be reached.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let (grid, [exit]) = Grid::from_char_grid("..#.\n.#E.", YAxis::TopDown, ['E'], Some).unwrap();
+///
+/// let path = nearest(&grid, [(0, 0), (3, 0)], |&loc| loc == exit, |_, &to| to != '#').unwrap();
+///
+/// assert_eq!(path.path(), vec![(3, 0), (3, 1), (2, 1)]);
+/// ```
+pub fn nearest<M, P, A>(
+    maze: &M,
+    sources: impl IntoIterator<Item = M::Location>,
+    matches: P,
+    allowed: A,
+) -> Option<ShortestPath<M::Location, usize>>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    P: FnMut(&M::Location) -> bool,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+{
+    let (steps, predecessors, goal) = search_steps(maze, sources, matches, allowed);
+    let goal = goal?;
+    Some(ShortestPath {
+        cost: steps[&goal],
+        goal,
+        predecessors,
+    })
+}
+
+/// Find the fewest steps from the nearest of `sources` to every location which can be reached
+/// from them, with a breadth-first search.
+///
+/// A step from one location to its neighbor is only taken if `allowed` returns true for the
+/// values at the two locations. Sources which are not in the maze are ignored.
+pub fn bfs_distances<M, A>(
+    maze: &M,
+    sources: impl IntoIterator<Item = M::Location>,
+    allowed: A,
+) -> DistanceField<M::Location, usize>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+{
+    let (costs, predecessors, _) = search_steps(maze, sources, |_| false, allowed);
+    DistanceField {
+        costs,
+        predecessors,
+    }
+}
+
+/// Find the cost of the cheapest path from the nearest of `sources` to every location which can
+/// be reached from them, with Dijkstra's algorithm.
+///
+/// Takes the same `allowed` and `cost` closures as `dijkstra`. Sources which are not in the
+/// maze are ignored.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let grid = Grid::from_fn(4, 1, |(x, _)| x as u32);
+/// let field = dijkstra_distances(&grid, [(0, 0), (3, 0)], |_, _| true, |_, &to| to);
+///
+/// assert_eq!(field.cost(&(2, 0)), Some(2));
+/// assert_eq!(field.path_to((2, 0)), Some(vec![(3, 0), (2, 0)]));
+/// ```
+pub fn dijkstra_distances<M, A, K, C>(
+    maze: &M,
+    sources: impl IntoIterator<Item = M::Location>,
+    allowed: A,
+    cost: K,
+) -> DistanceField<M::Location, C>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+    K: FnMut(&M::Value, &M::Value) -> C,
+    C: Copy + Ord + Add<Output = C> + Default,
+{
+    let (costs, predecessors, _) =
+        search_costs(maze, sources, |_| false, allowed, cost, |_| C::default());
+    DistanceField {
+        costs,
+        predecessors,
+    }
+}
+
+/// Find the cheapest path from `start` to a location for which `is_goal` returns true, with
+/// Dijkstra's algorithm.
+///
+/// A step from one location to its neighbor is only taken if `allowed` returns true for the
+/// values at the two locations, and then costs `cost` of those values. Costs must not be
+/// negative. Will return None if no goal can be reached.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let grid = Grid::from_fn(3, 3, |(x, y)| if (x, y) == (1, 1) { 9 } else { 1 });
+///
+/// // Stepping onto a location costs its value, so the path goes around the middle
+/// let path = dijkstra(&grid, (0, 1), |&loc| loc == (2, 1), |_, _| true, |_, &to| to).unwrap();
+///
+/// assert_eq!(path.cost, 4);
+/// assert_eq!(path.path().len(), 5);
+/// ```
+pub fn dijkstra<M, G, A, K, C>(
+    maze: &M,
+    start: M::Location,
+    is_goal: G,
+    allowed: A,
+    cost: K,
+) -> Option<ShortestPath<M::Location, C>>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    G: FnMut(&M::Location) -> bool,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+    K: FnMut(&M::Value, &M::Value) -> C,
+    C: Copy + Ord + Add<Output = C> + Default,
+{
+    astar(maze, start, is_goal, allowed, cost, |_| C::default())
+}
+
+/// Find the cheapest path from `start` to a location for which `is_goal` returns true, with
+/// the A* algorithm.
+///
+/// Takes the same arguments as `dijkstra`, along with a `heuristic` which estimates the cost
+/// from a location to the nearest goal. The path found is only guaranteed to be the cheapest
+/// if the heuristic never overestimates that cost. Will return None if no goal can be reached.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::maze::*;
+///
+/// let grid = Grid::new(50, 50, ());
+/// let goal = (40, 45);
+///
+/// let path = astar(
+///     &grid,
+///     (0, 0),
+///     |&loc| loc == goal,
+///     |_, _| true,
+///     |_, _| 1,
+///     |&(x, y)| x.abs_diff(goal.0) + y.abs_diff(goal.1),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.cost, 85);
+/// ```
+pub fn astar<M, G, A, K, H, C>(
+    maze: &M,
+    start: M::Location,
+    is_goal: G,
+    allowed: A,
+    cost: K,
+    heuristic: H,
+) -> Option<ShortestPath<M::Location, C>>
+where
+    M: ValuedMaze,
+    M::Location: Copy + Eq + Hash,
+    G: FnMut(&M::Location) -> bool,
+    A: FnMut(&M::Value, &M::Value) -> bool,
+    K: FnMut(&M::Value, &M::Value) -> C,
+    H: FnMut(&M::Location) -> C,
+    C: Copy + Ord + Add<Output = C> + Default,
+{
+    let (costs, predecessors, goal) =
+        search_costs(maze, [start], is_goal, allowed, cost, heuristic);
+    let goal = goal?;
+    Some(ShortestPath {
+        cost: costs[&goal],
+        goal,
+        predecessors,
+    })
+}
+
+/// A location waiting in the priority queue of a search, ordered so that the lowest priority
+/// is popped first from a max-heap.
+struct Queued<L, C> {
+    priority: C,
+    cost: C,
+    loc: L,
+}
+
+impl<L, C: Ord> PartialEq for Queued<L, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<L, C: Ord> Eq for Queued<L, C> {}
+
+impl<L, C: Ord> PartialOrd for Queued<L, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<L, C: Ord> Ord for Queued<L, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_map_occupied_maze_basic() {
+        let mut maze = HashMapMaze::<(usize, usize), _>::new();
+        maze.add_loc((0, 0), "start");
+        let mut maze = HashMapOccupiedMaze::from_hash_map_maze(maze, (0, 0)).unwrap();
+        maze.add_loc((1, 0), "one_right");
+        assert_eq!(maze.get_value(), &"start");
+        assert_eq!(
+            maze.get_value_at_loc(&maze.loc_right(maze.loc).unwrap())
+                .unwrap(),
+            &"one_right"
+        );
+    }
+
+    #[test]
+    fn hash_map_maze_impl_navigable_maze() {
+        let mut maze = HashMapMaze::<(usize, usize), _>::new();
+        for i in 0..10 {
+            for j in 0..10 {
+                maze.add_loc((i, j), ".")
+            }
+        }
+        assert_eq!(maze.loc_above((5, 5)).unwrap(), (5, 6));
+        assert_eq!(maze.loc_below((5, 5)).unwrap(), (5, 4));
+        assert_eq!(maze.loc_right((5, 5)).unwrap(), (6, 5));
+        assert_eq!(maze.loc_left((5, 5)).unwrap(), (4, 5));
+    }
+
+    #[test]
+    fn hash_map_maze_impl_navigable_maze_panics() {
+        let mut maze = HashMapMaze::<(usize, usize), _>::new();
+        for i in 0..10 {
+            for j in 0..10 {
+                maze.add_loc((i, j), ".")
+            }
+        }
+        assert_eq!(maze.loc_below((0, 0)).unwrap_or((666, 666)), (666, 666));
+        assert_eq!(maze.loc_left((0, 0)).unwrap_or((666, 666)), (666, 666));
+        assert_eq!(maze.loc_above((9, 9)).unwrap_or((666, 666)), (666, 666));
+        assert_eq!(maze.loc_right((9, 9)).unwrap_or((666, 666)), (666, 666));
+    }
+
+    #[test]
+    fn hash_map_occupied_maze_traverse_chars() {
+        let mut maze = HashMapMaze::<(usize, usize), _>::new();
+        maze.add_loc((0, 0), Block::Path('.'));
+        maze.add_loc((0, 1), Block::Path('.'));
+        maze.add_loc((0, 2), Block::Path('.'));
+        maze.add_loc((1, 0), Block::Path('.'));
+        maze.add_loc((1, 1), Block::Wall('x'));
+        maze.add_loc((1, 2), Block::Path('.'));
+        maze.add_loc((2, 0), Block::Path('.'));
+        maze.add_loc((2, 1), Block::Path('.'));
+        maze.add_loc((2, 2), Block::Path('.'));
+        let mut maze = HashMapOccupiedMaze::from_hash_map_maze(maze, (0, 0)).unwrap();
+        maze.move_up().unwrap();
+        maze.move_up().unwrap();
+        assert_eq!(maze.loc, (0, 2));
+        maze.move_right().unwrap();
+        maze.move_right().unwrap();
+        assert_eq!(maze.loc, (2, 2));
+        maze.move_down().unwrap();
+        maze.move_down().unwrap();
+        assert_eq!(maze.loc, (2, 0));
+        maze.move_left().unwrap();
+        assert_eq!(maze.loc, (1, 0));
+        let mut move_failed = false;
+        maze.move_up().unwrap_or_else(|_| {
+            move_failed = true;
+        });
+        assert!(move_failed)
+    }
+
+    #[test]
+    fn parse_char_grid_y_axis() {
+        let input = "ab\ncd";
+        let (maze, _): (HashMapMaze<_, _>, _) =
+            parse_char_grid(input, YAxis::TopDown, [], Some).unwrap();
+        assert_eq!(maze.get_value_at_loc(&(0, 0)).unwrap(), &'a');
+        assert_eq!(maze.get_value_at_loc(&(1, 1)).unwrap(), &'d');
+        let (maze, _): (HashMapMaze<_, _>, _) =
+            parse_char_grid(input, YAxis::BottomUp, [], Some).unwrap();
+        assert_eq!(maze.get_value_at_loc(&(0, 0)).unwrap(), &'c');
+        assert_eq!(maze.get_value_at_loc(&(1, 1)).unwrap(), &'b');
+    }
+
+    #[test]
+    fn parse_char_grid_into_vec() {
+        let (cells, [x]): (Vec<_>, _) =
+            parse_char_grid("12\n3x", YAxis::TopDown, ['x'], |c| Some(c.to_digit(10))).unwrap();
+        assert_eq!(x, (1, 1));
+        assert_eq!(
+            cells,
+            vec![
+                ((0, 0), Some(1)),
+                ((1, 0), Some(2)),
+                ((0, 1), Some(3)),
+                ((1, 1), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_char_grid_errors() {
+        let parse = |input, markers| {
+            HashMapMaze::from_char_grid(input, YAxis::TopDown, markers, |c| c.to_digit(10))
+                .map(|_| ())
+        };
+        assert_eq!(
+            parse("123\n45\n678", ['1']),
+            Err(ParseGridError::RaggedRow {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            parse("123\n4x6", ['1']),
+            Err(ParseGridError::UnknownChar {
+                line: 2,
+                column: 2,
+                c: 'x'
+            })
+        );
+        assert_eq!(parse("123", ['9']), Err(ParseGridError::MissingMarker('9')));
+        assert_eq!(
+            parse("121", ['1']),
+            Err(ParseGridError::DuplicateMarker('1'))
+        );
+    }
+
+    #[test]
+    fn signed_maze_extends_below_zero() {
+        let maze: HashMapMaze<(i32, i32), u8> = (-2..=2)
+            .flat_map(|x| (-2..=2).map(move |y| ((x, y), 0)))
+            .collect();
+        assert_eq!(maze.loc_below((0, 0)).unwrap(), (0, -1));
+        assert_eq!(maze.loc_left((0, 0)).unwrap(), (-1, 0));
+        assert_eq!(maze.loc_above((-1, -1)).unwrap(), (-1, 0));
+        assert_eq!(maze.loc_right((-1, -1)).unwrap(), (0, -1));
+        assert!(matches!(
+            maze.loc_left((-2, 0)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+    }
+
+    fn signed_edges_of_map<C>()
+    where
+        C: Coordinate + From<i8> + fmt::Debug,
+    {
+        let mut maze = HashMapMaze::<(C, C), _>::new();
+        for i in 0..10 {
+            for j in 0..10 {
+                maze.add_loc((C::from(i), C::from(j)), ".")
+            }
+        }
+        let (zero, nine) = (C::from(0), C::from(9));
+        assert!(matches!(
+            maze.loc_below((zero, zero)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            maze.loc_left((zero, zero)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            maze.loc_above((nine, nine)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            maze.loc_right((nine, nine)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        maze.add_loc((C::from(-1), zero), ".");
+        assert_eq!(maze.loc_left((zero, zero)).unwrap(), (C::from(-1), zero));
+    }
+
+    #[test]
+    fn i32_hash_map_maze_edges_of_map() {
+        signed_edges_of_map::<i32>();
+    }
+
+    #[test]
+    fn i64_hash_map_maze_edges_of_map() {
+        signed_edges_of_map::<i64>();
+    }
+
+    #[test]
+    fn moves_out_of_range_are_impossible() {
+        let maze: HashMapMaze<(i64, i64), u8> = [((i64::MIN, i64::MAX), 0)].into_iter().collect();
+        let corner = (i64::MIN, i64::MAX);
+        assert!(matches!(
+            maze.loc_left(corner),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert!(matches!(
+            maze.loc_above(corner),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert!(matches!(
+            maze.loc_right(corner),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        let maze: HashMapMaze<(usize, usize), u8> = [((0, 0), 0)].into_iter().collect();
+        assert!(matches!(
+            maze.loc_below((0, 0)),
+            Err(MazeError::ImpossibleMove)
+        ));
+    }
+
+    #[test]
+    fn signed_occupied_maze_moves_across_origin() {
+        let mut maze = HashMapMaze::new();
+        maze.add_loc((1, 0), Block::Path(()));
+        maze.add_loc((0, 0), Block::Path(()));
+        maze.add_loc((-1, 0), Block::Path(()));
+        maze.add_loc((-1, -1), Block::Wall(()));
+        let mut maze = HashMapOccupiedMaze::from_hash_map_maze(maze, (1i32, 0)).unwrap();
+        maze.move_left().unwrap();
+        maze.move_left().unwrap();
+        assert_eq!(maze.loc, (-1, 0));
+        assert!(matches!(maze.move_down(), Err(MazeError::ImpossibleMove)));
+        assert!(matches!(
+            maze.move_left(),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+    }
+
+    #[test]
+    fn grid_rows_and_columns() {
+        let grid = Grid::from_fn(3, 2, |(x, y)| 10 * y + x);
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[0, 1, 2][..], &[10, 11, 12][..]]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![0, 10], vec![1, 11], vec![2, 12]]
+        );
+        assert_eq!(grid.row(1), Some(&[10, 11, 12][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid[(2, 1)], 12);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &11)));
+
+        let empty: Grid<u8> = Grid::new(0, 3, 0);
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.columns().count(), 0);
+    }
+
+    #[test]
+    fn grid_impl_navigable_maze() {
+        let grid = Grid::new(3, 3, '.');
+        assert_eq!(grid.loc_above((1, 1)).unwrap(), (1, 2));
+        assert_eq!(grid.loc_below((1, 1)).unwrap(), (1, 0));
+        assert_eq!(grid.loc_right((1, 1)).unwrap(), (2, 1));
+        assert_eq!(grid.loc_left((1, 1)).unwrap(), (0, 1));
+        assert!(matches!(
+            grid.loc_above((1, 2)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            grid.loc_right((2, 0)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            grid.loc_below((1, 0)),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert!(matches!(
+            grid.loc_left((0, 1)),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert!(matches!(
+            grid.loc_below((5, 1)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+    }
+
+    #[test]
+    fn grid_from_char_grid_matches_hash_map_maze() {
+        let input = "#.S\n..#";
+        let f = |c| match c {
+            '#' => Some(Block::Wall(())),
+            '.' | 'S' => Some(Block::Path(c)),
+            _ => None,
+        };
+        let (grid, [start]) = Grid::from_char_grid(input, YAxis::BottomUp, ['S'], f).unwrap();
+        let (maze, [maze_start]) =
+            HashMapMaze::from_char_grid(input, YAxis::BottomUp, ['S'], f).unwrap();
+        assert_eq!(start, maze_start);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        for (loc, value) in grid.iter() {
+            assert_eq!(
+                matches!(value, Block::Wall(_)),
+                matches!(maze.get_value_at_loc(&loc).unwrap(), Block::Wall(_))
+            );
+        }
+
+        let mut grid = OccupiedGrid::from_grid(grid, start).unwrap();
+        assert!(matches!(
+            grid.move_up(),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(grid.move_down(), Err(MazeError::ImpossibleMove)));
+        grid.move_left().unwrap();
+        grid.move_down().unwrap();
+        grid.move_left().unwrap();
+        assert_eq!(grid.loc(), (0, 0));
+        assert!(matches!(grid.get_value(), Block::Path('.')));
+        assert!(OccupiedGrid::from_grid(Grid::new(1, 1, ()), (1, 0)).is_err());
+    }
+
+    #[test]
+    fn grid_hash_map_maze_conversions() {
+        let grid = Grid::from_fn(4, 3, |(x, y)| x * y);
+        let maze: HashMapMaze<(usize, usize), usize> = grid.clone().into();
+        assert_eq!(maze.get_value_at_loc(&(3, 2)).unwrap(), &6);
+        assert_eq!(Grid::try_from(maze).unwrap(), grid);
+
+        let mut maze = HashMapMaze::new();
+        maze.add_loc((0, 0), 'a');
+        maze.add_loc((1, 1), 'b');
+        assert!(matches!(
+            Grid::try_from(maze),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert_eq!(
+            Grid::try_from(HashMapMaze::<(usize, usize), u8>::new()).unwrap(),
+            Grid::new(0, 0, 0)
+        );
+        for loc in [(usize::MAX, 0), (0, usize::MAX)] {
+            let maze: HashMapMaze<(usize, usize), u8> = [(loc, 0)].into_iter().collect();
+            assert!(matches!(
+                Grid::try_from(maze),
+                Err(MazeError::LocationDoesNotExist)
+            ));
+        }
+        let maze: HashMapMaze<(usize, usize), u8> = [((1 << 40, 1 << 40), 0)].into_iter().collect();
+        assert!(matches!(
+            Grid::try_from(maze),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside a grid of size 2x2")]
+    fn grid_index_outside() {
+        let _ = Grid::new(2, 2, 0)[(0, 2)];
+    }
+
+    #[test]
+    fn diagonal_neighbors() {
+        let maze: HashMapMaze<(usize, usize), ()> = [(0, 0), (1, 1), (2, 0), (0, 2)]
+            .into_iter()
+            .map(|loc| (loc, ()))
+            .collect();
+        assert_eq!(maze.loc_below_left((1, 1)).unwrap(), (0, 0));
+        assert_eq!(maze.loc_below_right((1, 1)).unwrap(), (2, 0));
+        assert_eq!(maze.loc_above_left((1, 1)).unwrap(), (0, 2));
+        assert!(matches!(
+            maze.loc_above_right((1, 1)),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            maze.loc_below_left((0, 2)),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert!(matches!(
+            maze.loc_below_right((2, 0)),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert!(matches!(
+            maze.loc_offset((2, 0), -3, 0),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert_eq!(maze.loc_offset((2, 0), -2, 2).unwrap(), (0, 2));
+    }
+
+    #[test]
+    fn neighbors_in_order() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(
+            grid.neighbors4((1, 1)).collect::<Vec<_>>(),
+            vec![(1, 2), (1, 0), (0, 1), (2, 1)]
+        );
+        assert_eq!(
+            grid.neighbors8((1, 1)).collect::<Vec<_>>(),
+            vec![
+                (1, 2),
+                (1, 0),
+                (0, 1),
+                (2, 1),
+                (0, 2),
+                (2, 2),
+                (0, 0),
+                (2, 0)
+            ]
+        );
+        assert_eq!(
+            grid.neighbors8((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 1)]
+        );
+        assert_eq!(
+            grid.neighbors_with((2, 2), &DIAGONAL).collect::<Vec<_>>(),
+            vec![(1, 1)]
+        );
+
+        let maze: HashMapMaze<(i64, i64), ()> = (-2..=2)
+            .flat_map(|x| (-2..=2).map(move |y| ((x, y), ())))
+            .collect();
+        let mut jumps: Vec<_> = maze.neighbors_with((0, 0), &KNIGHT).collect();
+        jumps.sort();
+        assert_eq!(
+            jumps,
+            vec![
+                (-2, -1),
+                (-2, 1),
+                (-1, -2),
+                (-1, 2),
+                (1, -2),
+                (1, 2),
+                (2, -1),
+                (2, 1)
+            ]
+        );
+        let occupied = HashMapOccupiedMaze::from_hash_map_maze(maze, (0, 0)).unwrap();
+        assert_eq!(occupied.neighbors8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn coordinate_offsets() {
+        assert_eq!(5u8.offset(-5), Some(0));
+        assert_eq!(5u8.offset(-6), None);
+        assert_eq!(127i8.offset(-200), Some(-73));
+        assert_eq!(0u8.offset(256), None);
+        assert_eq!(u128::MAX.offset(-1), Some(u128::MAX - 1));
+        assert_eq!(u128::MAX.offset(1), None);
+        assert_eq!(i128::MIN.offset(-1), None);
+        assert_eq!(u64::MAX.offset(isize::MIN), Some(u64::MAX - (1 << 63)));
+    }
+
+    /// A maze which only provides the four required directions, with a hole at `(1, 1)`
+    struct HoleyMaze;
+
+    impl HoleyMaze {
+        fn existing_loc(loc: Option<(u8, u8)>) -> Result<(u8, u8), MazeError> {
+            match loc.ok_or(MazeError::ImpossibleMove)? {
+                (1, 1) | (3.., _) | (_, 3..) => Err(MazeError::LocationDoesNotExist),
+                loc => Ok(loc),
+            }
+        }
+    }
+
+    impl NavigableMaze for HoleyMaze {
+        type Location = (u8, u8);
+
+        fn loc_above(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+            Self::existing_loc(loc.1.checked_add(1).map(|y| (loc.0, y)))
+        }
+        fn loc_below(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+            Self::existing_loc(loc.1.checked_sub(1).map(|y| (loc.0, y)))
+        }
+        fn loc_right(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+            Self::existing_loc(loc.0.checked_add(1).map(|x| (x, loc.1)))
+        }
+        fn loc_left(&self, loc: Self::Location) -> Result<Self::Location, MazeError> {
+            Self::existing_loc(loc.0.checked_sub(1).map(|x| (x, loc.1)))
+        }
+    }
+
+    #[test]
+    fn default_loc_offset_steps() {
+        let maze = HoleyMaze;
+        assert_eq!(maze.loc_offset((0, 0), 0, 0).unwrap(), (0, 0));
+        assert_eq!(maze.loc_offset((0, 0), 2, 1).unwrap(), (2, 1));
+        assert_eq!(maze.loc_below_left((1, 2)).unwrap(), (0, 1));
+        assert!(matches!(
+            maze.loc_offset((0, 1), 2, 0),
+            Err(MazeError::LocationDoesNotExist)
+        ));
+        assert!(matches!(
+            maze.loc_offset((0, 0), -1, 0),
+            Err(MazeError::ImpossibleMove)
+        ));
+        assert_eq!(
+            maze.neighbors8((1, 0)).collect::<Vec<_>>(),
+            vec![(0, 0), (2, 0), (0, 1), (2, 1)]
+        );
+    }
+
+    const CLIMB: &str = "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi";
+
+    fn climb_grid() -> (Grid<u8>, [(usize, usize); 2]) {
+        Grid::from_char_grid(CLIMB, YAxis::TopDown, ['S', 'E'], |c| match c {
+            'S' => Some(0),
+            'E' => Some(25),
+            _ => Some(c as u8 - b'a'),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn bfs_climb() {
+        let (grid, [start, end]) = climb_grid();
+        let path = bfs(&grid, start, |&loc| loc == end, |&from, &to| to <= from + 1).unwrap();
+        assert_eq!(path.cost, 31);
+        let locs = path.path();
+        assert_eq!(locs.len(), 32);
+        assert_eq!((locs[0], locs[31]), (start, end));
+        for step in locs.windows(2) {
+            assert_eq!(
+                step[0].0.abs_diff(step[1].0) + step[0].1.abs_diff(step[1].1),
+                1
+            );
+            assert!(grid[step[1]] <= grid[step[0]] + 1);
+        }
+        assert_eq!(path.predecessors().get(&start), None);
+
+        // Searching backwards from the end for the nearest lowest location
+        let path = bfs(
+            &grid,
+            end,
+            |&loc| grid[loc] == 0,
+            |&from, &to| from <= to + 1,
+        )
+        .unwrap();
+        assert_eq!(path.cost, 29);
+    }
+
+    #[test]
+    fn searches_agree_on_cost() {
+        // Walls, with value 0, across the whole grid apart from one gap each
+        let grid = Grid::from_fn(12, 9, |(x, y)| match (x, y) {
+            (4, 0..=6) | (8, 2..=8) => 0,
+            _ => ((x * 7 + y * 13) % 5) as u32 + 1,
+        });
+        let goal = (11, 8);
+        let allowed = |_: &u32, &to: &u32| to != 0;
+        let by_dijkstra =
+            dijkstra(&grid, (0, 0), |&loc| loc == goal, allowed, |_, &to| to).unwrap();
+        let by_astar = astar(
+            &grid,
+            (0, 0),
+            |&loc| loc == goal,
+            allowed,
+            |_, &to| to,
+            |&(x, y)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u32,
+        )
+        .unwrap();
+        assert_eq!(by_dijkstra.cost, by_astar.cost);
+        for path in [by_dijkstra, by_astar] {
+            let locs = path.path();
+            let total: u32 = locs[1..].iter().map(|&loc| grid[loc]).sum();
+            assert_eq!(total, path.cost);
+            assert!(locs.iter().all(|&loc| grid[loc] != 0));
+        }
+
+        let (grid, [start, end]) = climb_grid();
+        let steps = dijkstra(
+            &grid,
+            start,
+            |&loc| loc == end,
+            |&from, &to| to <= from + 1,
+            |_, _| 1,
+        )
+        .unwrap();
+        assert_eq!(steps.cost, 31);
+    }
+
+    #[test]
+    fn searches_without_a_path() {
+        let mut maze: HashMapMaze<(i32, i32), Block<(), ()>> =
+            (-3..=3).map(|x| ((x, 0), Block::Path(()))).collect();
+        maze.add_loc((0, 0), Block::Wall(()));
+        let allowed = |_: &Block<(), ()>, to: &Block<(), ()>| matches!(to, Block::Path(_));
+        assert_eq!(
+            bfs(&maze, (-3, 0), |&loc| loc == (-1, 0), allowed)
+                .unwrap()
+                .cost,
+            2
+        );
+        assert!(bfs(&maze, (-3, 0), |&loc| loc == (3, 0), allowed).is_none());
+        assert!(dijkstra(&maze, (-3, 0), |&loc| loc == (3, 0), allowed, |_, _| 1).is_none());
+        // The start must be in the maze
+        assert!(bfs(&maze, (0, 5), |_| true, allowed).is_none());
+        // The start itself may be the goal
+        let path = bfs(&maze, (3, 0), |_| true, allowed).unwrap();
+        assert_eq!((path.cost, path.path()), (0, vec![(3, 0)]));
+    }
+
+    #[test]
+    fn distance_field_from_many_sources() {
+        let (grid, [start, end]) = climb_grid();
+        let lowest: Vec<_> = grid
+            .iter()
+            .filter(|(_, &height)| height == 0)
+            .map(|(loc, _)| loc)
+            .collect();
+        let climbable = |&from: &u8, &to: &u8| to <= from + 1;
+        let field = bfs_distances(&grid, lowest.iter().copied(), climbable);
+        assert_eq!(field.cost(&end), Some(29));
+        assert!(lowest.iter().all(|loc| field.cost(loc) == Some(0)));
+        let path = field.path_to(end).unwrap();
+        assert_eq!(path.len(), 30);
+        assert_eq!(grid[path[0]], 0);
+        assert_eq!(field.len(), grid.width() * grid.height());
+
+        // Every location can reach the end, and from the end a reversed search finds them all
+        let back = bfs_distances(&grid, [end], reversed(climbable));
+        assert_eq!(back.len(), field.len());
+        assert_eq!(back.nearest(|&loc| grid[loc] == 0).unwrap().1, 29);
+        assert_eq!(back.cost(&start), Some(31));
+        assert_eq!(
+            nearest(&grid, [end], |&loc| grid[loc] == 0, reversed(climbable))
+                .unwrap()
+                .cost,
+            29
+        );
+        assert!(back.nearest(|&loc| grid[loc] > 25).is_none());
+    }
+
+    #[test]
+    fn weighted_distance_field() {
+        let maze: HashMapMaze<(i32, i32), u32> = (-5i32..=5)
+            .flat_map(|x| (-1..=1).map(move |y| ((x, y), x.unsigned_abs() + 1)))
+            .collect();
+        let field = dijkstra_distances(&maze, [(-5, 0), (5, 0), (9, 9)], |_, _| true, |_, &to| to);
+        // Costs of entering x = -4, ..., 0 are 5 + 4 + 3 + 2 + 1
+        assert_eq!(field.cost(&(0, 0)), Some(15));
+        assert_eq!(field.cost(&(5, 1)), Some(6));
+        assert_eq!(field.cost(&(9, 9)), None);
+        assert_eq!(field.path_to((9, 9)), None);
+        assert_eq!(
+            field.path_to((-4, 1)).unwrap(),
+            vec![(-5, 0), (-4, 0), (-4, 1)]
+        );
+        assert_eq!(field.len(), 33);
+        assert_eq!(field.predecessors().len(), 31);
+
+        let field = dijkstra_distances(&maze, [(9, 9)], |_, _| true, |_, &to| to);
+        assert!(field.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::super::*;
+
+        #[test]
+        fn hash_map_occupied_maze_round_trip() {
+            let (maze, [start]) =
+                HashMapMaze::from_char_grid("S.\n#.", YAxis::BottomUp, ['S'], |c| match c {
+                    'S' | '.' => Some(Block::Path(c)),
+                    '#' => Some(Block::Wall(())),
+                    _ => None,
+                })
+                .unwrap();
+            let mut maze = HashMapOccupiedMaze::from_hash_map_maze(maze, start).unwrap();
+            maze.move_right().unwrap();
+            let json = serde_json::to_string(&maze).unwrap();
+
+            let mut maze: HashMapOccupiedMaze<(usize, usize), Block<char, ()>> =
+                serde_json::from_str(&json).unwrap();
+            assert!(matches!(maze.get_value(), Block::Path('.')));
+            assert!(matches!(
+                maze.get_value_at_loc(&(0, 1)).unwrap(),
+                Block::Path('S')
+            ));
+            maze.move_down().unwrap();
+            assert!(matches!(maze.move_left(), Err(MazeError::ImpossibleMove)));
+            assert!(matches!(maze.move_up(), Ok(())));
+        }
+
+        #[test]
+        fn hash_map_maze_serializes_as_pairs() {
+            let maze: HashMapMaze<(usize, usize), u8> = [((1, 2), 3)].into_iter().collect();
+            let json = serde_json::to_string(&maze).unwrap();
+            assert_eq!(json, "[[[1,2],3]]");
+            let maze: HashMapMaze<(usize, usize), u8> = serde_json::from_str(&json).unwrap();
+            assert_eq!(maze.get_value_at_loc(&(1, 2)).unwrap(), &3);
+        }
+
+        #[test]
+        fn grid_round_trip() {
+            let grid =
+                OccupiedGrid::from_grid(Grid::from_fn(3, 2, |(x, y)| x + y), (2, 1)).unwrap();
+            let json = serde_json::to_string(&grid).unwrap();
+            let copy: OccupiedGrid<usize> = serde_json::from_str(&json).unwrap();
+            assert_eq!(copy.grid(), grid.grid());
+            assert_eq!(copy.loc(), (2, 1));
+
+            assert!(
+                serde_json::from_str::<Grid<u8>>(r#"{"width":2,"height":2,"cells":[1,2,3]}"#)
+                    .is_err()
+            );
+            assert!(serde_json::from_str::<OccupiedGrid<u8>>(
+                r#"{"grid":{"width":1,"height":1,"cells":[1]},"loc":[0,1]}"#
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn occupant_must_be_in_maze() {
+            let json = r#"{"maze":[[[0,0],"a"]],"loc":[0,1]}"#;
+            assert!(
+                serde_json::from_str::<HashMapOccupiedMaze<(usize, usize), String>>(json).is_err()
+            );
+        }
     }
 }
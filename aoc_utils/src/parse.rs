@@ -0,0 +1,250 @@
+#![warn(missing_docs)]
+
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, line_ending, multispace0, none_of, space0, space1};
+use nom::combinator::{map_res, opt, recognize};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::{Finish, IResult};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error returned by `run_parser` when a parser fails or does not consume all of its input.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    /// 1-based line number at which parsing failed
+    pub line: usize,
+    /// 1-based column number, in characters, at which parsing failed
+    pub column: usize,
+    /// Description of what went wrong
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}:{}", self.message, self.line, self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+impl ParseError {
+    fn at(input: &str, remaining: &str, message: String) -> Self {
+        let consumed = &input[..input.len() - remaining.len()];
+        let line = consumed.matches('\n').count() + 1;
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        let column = consumed[line_start..].chars().count() + 1;
+        Self {
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+/// Run `parser` over the whole of `input`, ignoring trailing whitespace.
+///
+/// Will return an error with the line and column at which parsing failed if the parser fails
+/// or leaves any non-whitespace input unconsumed.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// assert_eq!(run_parser(comma_list(signed::<i32>), "1, -2,3\n"), Ok(vec![1, -2, 3]));
+///
+/// let err = run_parser(comma_list(signed::<i32>), "1,2\n3").unwrap_err();
+/// assert_eq!((err.line, err.column), (2, 1));
+/// ```
+pub fn run_parser<'a, O, F>(parser: F, input: &'a str) -> Result<O, ParseError>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    let (remaining, output) = terminated(parser, multispace0)(input)
+        .finish()
+        .map_err(|e| {
+            ParseError::at(
+                input,
+                e.input,
+                format!("parser failed ({})", e.code.description()),
+            )
+        })?;
+    if remaining.is_empty() {
+        Ok(output)
+    } else {
+        Err(ParseError::at(
+            input,
+            remaining,
+            "unexpected trailing input".to_string(),
+        ))
+    }
+}
+
+/// Parse an unsigned integer into any type which implements `FromStr`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// assert_eq!(unsigned::<u64>("123 abc"), Ok((" abc", 123)));
+/// assert!(unsigned::<u64>("-123").is_err());
+/// ```
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// Parse an integer with an optional leading `-` into any type which implements `FromStr`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// assert_eq!(signed::<i32>("-123 abc"), Ok((" abc", -123)));
+/// ```
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// Parse one or more items separated by spaces or tabs.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// assert_eq!(ws_list(unsigned::<u8>)("1  2\t3\n4"), Ok(("\n4", vec![1, 2, 3])));
+/// ```
+pub fn ws_list<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_list1(space1, f)
+}
+
+/// Parse one or more items separated by commas, optionally followed by spaces.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// assert_eq!(comma_list(unsigned::<u8>)("1,2, 3"), Ok(("", vec![1, 2, 3])));
+/// ```
+pub fn comma_list<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_list1(pair(char(','), space0), f)
+}
+
+/// Parse a labelled `key: value` field, returning the value.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// let mut seeds = field("seeds", ws_list(unsigned::<u64>));
+///
+/// assert_eq!(seeds("seeds: 79 14 55 13"), Ok(("", vec![79, 14, 55, 13])));
+/// ```
+pub fn field<'a, O, F>(key: &'a str, f: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    preceded(tuple((tag(key), char(':'), space0)), f)
+}
+
+/// Parse a grid of one or more lines, each made up of one or more cells parsed by `cell`.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+/// use nom::character::complete::one_of;
+///
+/// let mut trees = grid(one_of("0123456789"));
+///
+/// assert_eq!(trees("30\n25"), Ok(("", vec![vec!['3', '0'], vec!['2', '5']])));
+/// ```
+pub fn grid<'a, O, F>(cell: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<Vec<O>>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_list1(line_ending, many1(cell))
+}
+
+/// Parse a grid of one or more lines of arbitrary characters.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+///
+/// assert_eq!(char_grid("#.\n.#"), Ok(("", vec![vec!['#', '.'], vec!['.', '#']])));
+/// ```
+pub fn char_grid(input: &str) -> IResult<&str, Vec<Vec<char>>> {
+    grid(none_of("\r\n"))(input)
+}
+
+/// Parse one or more sections separated by one or more blank lines.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::parse::*;
+/// use nom::character::complete::line_ending;
+/// use nom::multi::separated_list1;
+///
+/// let mut elves = sections(separated_list1(line_ending, unsigned::<u32>));
+///
+/// assert_eq!(elves("1\n2\n\n\n3"), Ok(("", vec![vec![1, 2], vec![3]])));
+/// ```
+pub fn sections<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    separated_list1(pair(line_ending, many1(line_ending)), f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_parser_trailing_input() {
+        let err = run_parser(ws_list(unsigned::<u32>), "1 2 3\n4 x").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                line: 2,
+                column: 1,
+                message: "unexpected trailing input".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn run_parser_failure_position() {
+        let err = run_parser(field("seeds", ws_list(unsigned::<u32>)), "seeds: x").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 8));
+        let err = run_parser(field("seeds", unsigned::<u32>), "\nseedz: 1").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(err.message, "parser failed (Tag)");
+    }
+
+    #[test]
+    fn signed_and_unsigned_overflow() {
+        assert!(unsigned::<u8>("256").is_err());
+        assert_eq!(signed::<i8>("-128"), Ok(("", -128)));
+        assert!(signed::<i8>("-129").is_err());
+    }
+
+    #[test]
+    fn sections_of_char_grids() {
+        let input = "#.\n.#\r\n\r\n..\n##\n";
+        let result = run_parser(sections(char_grid), input).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                vec![vec!['#', '.'], vec!['.', '#']],
+                vec![vec!['.', '.'], vec!['#', '#']]
+            ]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+#![warn(missing_docs)]
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "derive")]
+pub use aoc_utils_derive::FromPattern;
+
+/// Error returned when a string does not match a pattern.
+#[derive(Debug, PartialEq)]
+pub enum PatternError {
+    /// Raised when literal text from the pattern is not found where expected
+    ExpectedLiteral {
+        /// The literal text which was expected
+        literal: String,
+        /// 0-based byte offset at which it was expected
+        position: usize,
+    },
+    /// Raised when there is text left over after the whole pattern has matched
+    TrailingInput {
+        /// 0-based byte offset of the leftover text
+        position: usize,
+    },
+    /// Raised when the text captured for a field fails to parse
+    InvalidField {
+        /// Name of the field which failed to parse
+        field: String,
+        /// Text captured for the field
+        text: String,
+        /// 0-based byte offset of the captured text
+        position: usize,
+        /// Description of the underlying parse error
+        message: String,
+    },
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::ExpectedLiteral { literal, position } => {
+                write!(f, "expected {:?} at position {}", literal, position)
+            }
+            PatternError::TrailingInput { position } => {
+                write!(f, "unexpected trailing input at position {}", position)
+            }
+            PatternError::InvalidField {
+                field,
+                text,
+                position,
+                message,
+            } => write!(
+                f,
+                "failed to parse field `{}` from {:?} at position {}: {}",
+                field, text, position, message
+            ),
+        }
+    }
+}
+
+impl Error for PatternError {}
+
+/// Text captured for one field of a pattern.
+#[derive(Debug, PartialEq)]
+pub struct Capture<'a> {
+    /// The captured text
+    pub text: &'a str,
+    /// 0-based byte offset of the captured text in the matched string, after any leading
+    /// whitespace
+    pub position: usize,
+}
+
+/// Match `s` against a pattern made up of `literals` with a field between each pair of them.
+///
+/// `literals` must have exactly one more element than `fields`. Each field's text runs up to
+/// the first occurrence of the literal after it, or to the end of `s` if that literal is
+/// empty, and has surrounding whitespace trimmed. This is the matcher used by
+/// `#[derive(FromPattern)]`.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::pattern;
+///
+/// let captures = pattern::match_pattern("4x 5 x8", &["", "x", "x", ""], &["l", "w", "h"]).unwrap();
+///
+/// assert_eq!(captures.iter().map(|c| c.text).collect::<Vec<_>>(), vec!["4", "5", "8"]);
+/// assert_eq!(captures[1].position, 3);
+/// ```
+pub fn match_pattern<'a>(
+    s: &'a str,
+    literals: &[&str],
+    fields: &[&str],
+) -> Result<Vec<Capture<'a>>, PatternError> {
+    assert_eq!(
+        literals.len(),
+        fields.len() + 1,
+        "Pattern has one more literal than fields"
+    );
+    let expect_literal = |pos: usize, literal: &str| {
+        if s[pos..].starts_with(literal) {
+            Ok(pos + literal.len())
+        } else {
+            Err(PatternError::ExpectedLiteral {
+                literal: literal.to_string(),
+                position: pos,
+            })
+        }
+    };
+    let mut pos = expect_literal(0, literals[0])?;
+    let mut captures = Vec::with_capacity(fields.len());
+    for literal in &literals[1..] {
+        let end = if literal.is_empty() {
+            s.len()
+        } else {
+            match s[pos..].find(literal) {
+                Some(i) => pos + i,
+                None => {
+                    return Err(PatternError::ExpectedLiteral {
+                        literal: literal.to_string(),
+                        position: s.len(),
+                    })
+                }
+            }
+        };
+        let text = s[pos..end].trim_start();
+        captures.push(Capture {
+            text: text.trim_end(),
+            position: end - text.len(),
+        });
+        pos = expect_literal(end, literal)?;
+    }
+    if pos != s.len() {
+        return Err(PatternError::TrailingInput { position: pos });
+    }
+    Ok(captures)
+}
+
+/// Parse the text captured for `field` with its `FromStr` implementation.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_utils::pattern::{self, Capture};
+///
+/// let capture = Capture { text: "12", position: 3 };
+///
+/// assert_eq!(pattern::parse_capture::<u8>(&capture, "x"), Ok(12));
+/// assert!(pattern::parse_capture::<bool>(&capture, "x").is_err());
+/// ```
+pub fn parse_capture<T>(capture: &Capture, field: &str) -> Result<T, PatternError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    capture
+        .text
+        .parse()
+        .map_err(|e: T::Err| PatternError::InvalidField {
+            field: field.to_string(),
+            text: capture.text.to_string(),
+            position: capture.position,
+            message: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_pattern_literal_errors() {
+        let literals = ["Sensor at x=", ", y=", ""];
+        assert_eq!(
+            match_pattern("Beacon at x=1, y=2", &literals, &["x", "y"]),
+            Err(PatternError::ExpectedLiteral {
+                literal: "Sensor at x=".to_string(),
+                position: 0
+            })
+        );
+        assert_eq!(
+            match_pattern("Sensor at x=1; y=2", &literals, &["x", "y"]),
+            Err(PatternError::ExpectedLiteral {
+                literal: ", y=".to_string(),
+                position: 18
+            })
+        );
+    }
+
+    #[test]
+    fn match_pattern_trims_captures() {
+        assert_eq!(
+            match_pattern(" 1 ->\t2 ", &["", "->", ""], &["a", "b"]),
+            Ok(vec![
+                Capture {
+                    text: "1",
+                    position: 1
+                },
+                Capture {
+                    text: "2",
+                    position: 6
+                }
+            ])
+        );
+        assert_eq!(
+            match_pattern("( )", &["(", ")"], &["a"]),
+            Ok(vec![Capture {
+                text: "",
+                position: 2
+            }])
+        );
+    }
+
+    #[test]
+    fn match_pattern_trailing_input() {
+        assert_eq!(
+            match_pattern("(1)(2)", &["(", ")"], &["a"]),
+            Err(PatternError::TrailingInput { position: 3 })
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use super::super::*;
+
+        #[derive(Debug, PartialEq, FromPattern)]
+        #[pattern("{x1},{y1} -> {x2},{y2}")]
+        struct Line {
+            x1: i32,
+            y1: i32,
+            x2: i32,
+            y2: i32,
+        }
+
+        #[derive(Debug, PartialEq, FromPattern)]
+        #[pattern("{{{1}: {0}}}")]
+        struct Swapped(String, u8);
+
+        #[test]
+        fn derive_named_fields() {
+            assert_eq!(
+                "0,-9 -> 5,9".parse(),
+                Ok(Line {
+                    x1: 0,
+                    y1: -9,
+                    x2: 5,
+                    y2: 9
+                })
+            );
+        }
+
+        #[test]
+        fn derive_reports_failed_field() {
+            match "0,9 -> five,9".parse::<Line>() {
+                Err(PatternError::InvalidField {
+                    field,
+                    text,
+                    position,
+                    message,
+                }) => assert_eq!(
+                    (field.as_str(), text.as_str(), position, message.as_str()),
+                    ("x2", "five", 7, "invalid digit found in string")
+                ),
+                other => panic!("Unexpected result {:?}", other),
+            }
+            assert!("0,9 -> 5".parse::<Line>().is_err());
+        }
+
+        #[test]
+        fn derive_tuple_struct() {
+            assert_eq!("{7: seven}".parse(), Ok(Swapped("seven".to_string(), 7)));
+        }
+    }
+}
@@ -0,0 +1,311 @@
+#![warn(missing_docs)]
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Name of the directory, relative to a crate's manifest directory, that sample fixtures are
+/// written to and loaded from.
+pub const SAMPLE_DIR_NAME: &str = "samples";
+
+/// The examples and emphasized answers from one part of a puzzle page.
+#[derive(Debug, Default, PartialEq)]
+pub struct PuzzlePart {
+    /// Text of every `<pre><code>` example block, in order
+    pub examples: Vec<String>,
+    /// Text of every emphasized `<code>` in the part, in order
+    pub answers: Vec<String>,
+    /// Answer to each of `examples`, which is the last emphasized `<code>` between that example
+    /// and the next one
+    pub example_answers: Vec<Option<String>>,
+}
+
+impl PuzzlePart {
+    /// Get the answer to the part, which is the last emphasized `<code>` in the part.
+    pub fn answer(&self) -> Option<&str> {
+        self.answers.last().map(|s| s.as_str())
+    }
+
+    /// Get the answer to example `example` (0-based) of the part.
+    pub fn example_answer(&self, example: usize) -> Option<&str> {
+        self.example_answers.get(example)?.as_deref()
+    }
+}
+
+/// Replace the HTML entities used on puzzle pages with the characters they represent.
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Remove any tags, e.g. `<em>` highlighting inside an example block, and unescape the rest.
+fn strip_tags(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => res.push(c),
+            _ => (),
+        }
+    }
+    unescape(&res)
+}
+
+/// Find the contents of every `open ... close` span in `html`, in order, along with the
+/// position each span starts at.
+fn spans<'a>(html: &'a str, open: &str, close: &str) -> Vec<(usize, &'a str)> {
+    let mut res = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = html[pos..].find(open) {
+        let content_start = pos + start + open.len();
+        match html[content_start..].find(close) {
+            Some(end) => {
+                res.push((content_start, &html[content_start..content_start + end]));
+                pos = content_start + end + close.len();
+            }
+            None => break,
+        }
+    }
+    res
+}
+
+fn parse_article(article: &str) -> PuzzlePart {
+    let examples: Vec<String> = spans(article, "<pre><code>", "</code></pre>")
+        .into_iter()
+        .map(|(_, example)| strip_tags(example))
+        .collect();
+    // Answers are written as either <code><em>24000</em></code> or <em><code>24000</code></em>.
+    // Example blocks are removed first so that highlighting inside them isn't picked up, and
+    // where each example was is remembered so that answers can be matched up with it.
+    let mut prose = String::with_capacity(article.len());
+    let mut example_ends = Vec::new();
+    let mut rest = article;
+    while let Some(start) = rest.find("<pre>") {
+        prose.push_str(&rest[..start]);
+        if rest[start..].starts_with("<pre><code>") {
+            example_ends.push(prose.len());
+        }
+        rest = match rest[start..].find("</pre>") {
+            Some(end) => &rest[start + end + "</pre>".len()..],
+            None => "",
+        };
+    }
+    prose.push_str(rest);
+    let mut answers = spans(&prose, "<code><em>", "</em></code>");
+    answers.extend(spans(&prose, "<em><code>", "</code></em>"));
+    answers.sort_by_key(|(pos, _)| *pos);
+    let answers: Vec<(usize, String)> = answers
+        .into_iter()
+        .map(|(pos, answer)| (pos, strip_tags(answer)))
+        .collect();
+    let example_answers = example_ends
+        .iter()
+        .enumerate()
+        .map(|(i, &end)| {
+            let next = example_ends.get(i + 1).copied().unwrap_or(usize::MAX);
+            answers
+                .iter()
+                .rev()
+                .find(|(pos, _)| (end..next).contains(pos))
+                .map(|(_, answer)| answer.clone())
+        })
+        .collect();
+    PuzzlePart {
+        examples,
+        answers: answers.into_iter().map(|(_, answer)| answer).collect(),
+        example_answers,
+    }
+}
+
+/// Extract the examples and answers for each part from a saved puzzle page.
+///
+/// Each `<article>` on the page is one part. If a later part has no example blocks of its own,
+/// it reuses the examples from the part before it, since part two usually builds on the same
+/// example as part one. The answer to such a part is taken to be the answer to the first of
+/// those examples.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::sample;
+///
+/// let html = "<article class=\"day-desc\"><p>For example:</p>\
+///     <pre><code>1\n2 &lt; 3\n</code></pre>\
+///     <p>The answer is <code><em>6</em></code>.</p></article>\
+///     <article class=\"day-desc\"><p>Now the answer is <code><em>7</em></code>.</p></article>";
+///
+/// let parts = sample::parse_puzzle_html(html);
+///
+/// assert_eq!(parts.len(), 2);
+/// assert_eq!(parts[0].examples, vec!["1\n2 < 3\n"]);
+/// assert_eq!(parts[0].answer(), Some("6"));
+/// assert_eq!(parts[0].example_answer(0), Some("6"));
+/// assert_eq!(parts[1].examples, parts[0].examples);
+/// assert_eq!(parts[1].example_answer(0), Some("7"));
+/// ```
+pub fn parse_puzzle_html(html: &str) -> Vec<PuzzlePart> {
+    let mut parts: Vec<PuzzlePart> = Vec::new();
+    for (_, article) in spans(html, "<article", "</article>") {
+        let mut part = parse_article(article);
+        if part.examples.is_empty() {
+            if let Some(previous) = parts.last() {
+                part.examples = previous.examples.clone();
+                part.example_answers = vec![None; part.examples.len()];
+                if let Some(first) = part.example_answers.first_mut() {
+                    *first = part.answers.last().cloned();
+                }
+            }
+        }
+        parts.push(part);
+    }
+    parts
+}
+
+/// Write the examples and answers of each part to `dir` as fixture files.
+///
+/// Example `m` of part `n` is written to `part{n}_example{m}.txt` and its answer to
+/// `part{n}_example{m}_answer.txt`, both 1-based. The answer to the part as a whole, which is
+/// the answer to its last example, is also written to `part{n}_answer.txt`. These can be loaded
+/// back with `load_example`, `load_example_answer` and `load_answer`.
+pub fn write_fixtures(parts: &[PuzzlePart], dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (i, part) in parts.iter().enumerate() {
+        for (j, example) in part.examples.iter().enumerate() {
+            fs::write(
+                dir.join(format!("part{}_example{}.txt", i + 1, j + 1)),
+                example,
+            )?;
+        }
+        for (j, answer) in part.example_answers.iter().enumerate() {
+            if let Some(answer) = answer {
+                fs::write(
+                    dir.join(format!("part{}_example{}_answer.txt", i + 1, j + 1)),
+                    answer,
+                )?;
+            }
+        }
+        if let Some(answer) = part.answer() {
+            fs::write(dir.join(format!("part{}_answer.txt", i + 1)), answer)?;
+        }
+    }
+    Ok(())
+}
+
+/// Load example `example` of part `part` (both 1-based) from the `samples` directory in
+/// `manifest_dir`, which should be `env!("CARGO_MANIFEST_DIR")`.
+pub fn load_example(manifest_dir: &str, part: usize, example: usize) -> io::Result<String> {
+    fs::read_to_string(
+        Path::new(manifest_dir)
+            .join(SAMPLE_DIR_NAME)
+            .join(format!("part{}_example{}.txt", part, example)),
+    )
+}
+
+/// Load the answer to example `example` of part `part` (both 1-based) from the `samples`
+/// directory in `manifest_dir`, which should be `env!("CARGO_MANIFEST_DIR")`.
+pub fn load_example_answer(manifest_dir: &str, part: usize, example: usize) -> io::Result<String> {
+    fs::read_to_string(
+        Path::new(manifest_dir)
+            .join(SAMPLE_DIR_NAME)
+            .join(format!("part{}_example{}_answer.txt", part, example)),
+    )
+}
+
+/// Load the answer to part `part` (1-based), which is the answer to its last example, from the
+/// `samples` directory in `manifest_dir`, which should be `env!("CARGO_MANIFEST_DIR")`.
+pub fn load_answer(manifest_dir: &str, part: usize) -> io::Result<String> {
+    fs::read_to_string(
+        Path::new(manifest_dir)
+            .join(SAMPLE_DIR_NAME)
+            .join(format!("part{}_answer.txt", part)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2>
+<p>For example, suppose the Elves finish writing their items' <code>Calories</code>:</p>
+<pre><code>1000
+2000
+
+<em>4000</em>
+</code></pre>
+<p>This Elf is carrying <em>the most</em> Calories: <code><em>24000</em></code>.</p>
+<p>Another example:</p>
+<pre><code>a &amp; b -&gt; c
+</code></pre>
+<p>It gives <em><code>abc</code></em>.</p>
+</article>
+<p>Your puzzle answer was <code>71506</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Now with different input:</p>
+<pre><code>x
+y
+</code></pre>
+<p>The first total is <code><em>1</em></code> and the final total is <code><em>45000</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn parse_puzzle_html_multiple_examples_and_parts() {
+        let parts = parse_puzzle_html(PAGE);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(
+            parts[0].examples,
+            vec!["1000\n2000\n\n4000\n", "a & b -> c\n"]
+        );
+        assert_eq!(parts[0].answers, vec!["24000", "abc"]);
+        assert_eq!(parts[1].examples, vec!["x\ny\n"]);
+        assert_eq!(parts[1].answers, vec!["1", "45000"]);
+        assert_eq!(parts[1].answer(), Some("45000"));
+        assert_eq!(
+            parts[0].example_answers,
+            vec![Some("24000".to_string()), Some("abc".to_string())]
+        );
+        assert_eq!(parts[1].example_answer(0), Some("45000"));
+        assert_eq!(parts[1].example_answer(1), None);
+    }
+
+    #[test]
+    fn parse_puzzle_html_reused_examples() {
+        let parts = parse_puzzle_html(
+            "<article><pre><code>a</code></pre><pre><code>b</code></pre>\
+            <p><code><em>1</em></code></p></article>\
+            <article><p><code><em>2</em></code></p></article>",
+        );
+        assert_eq!(parts[0].example_answers, vec![None, Some("1".to_string())]);
+        assert_eq!(parts[1].examples, parts[0].examples);
+        assert_eq!(parts[1].example_answers, vec![Some("2".to_string()), None]);
+    }
+
+    #[test]
+    fn parse_puzzle_html_no_articles() {
+        assert_eq!(parse_puzzle_html("<html></html>"), vec![]);
+    }
+
+    #[test]
+    fn write_and_load_fixtures() {
+        let crate_dir = env::temp_dir().join(format!("aoc_utils_{}_sample", std::process::id()));
+        let _ = fs::remove_dir_all(&crate_dir);
+        let parts = parse_puzzle_html(PAGE);
+        write_fixtures(&parts, &crate_dir.join(SAMPLE_DIR_NAME)).unwrap();
+
+        let crate_dir = crate_dir.to_str().unwrap();
+        assert_eq!(load_example(crate_dir, 1, 2).unwrap(), "a & b -> c\n");
+        assert_eq!(load_example(crate_dir, 2, 1).unwrap(), "x\ny\n");
+        assert_eq!(load_example_answer(crate_dir, 1, 1).unwrap(), "24000");
+        assert_eq!(load_example_answer(crate_dir, 1, 2).unwrap(), "abc");
+        assert_eq!(load_example_answer(crate_dir, 2, 1).unwrap(), "45000");
+        assert_eq!(load_answer(crate_dir, 1).unwrap(), "abc");
+        assert_eq!(load_answer(crate_dir, 2).unwrap(), "45000");
+        assert!(load_example(crate_dir, 2, 2).is_err());
+    }
+}
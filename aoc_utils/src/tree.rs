@@ -1,62 +1,282 @@
 #![warn(missing_docs)]
 
-/// Empty struct returns when a requested node does not exist in the tree.
-#[derive(Debug)]
-pub struct NodeDoesNotExist {}
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Deref, Index, IndexMut};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Identifier of a node in an ArenaTree<T>
+///
+/// Ids are handed out by the tree when nodes are added. Each tree tags its ids, so an id from
+/// one tree is never mistaken for a node of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+    tree: u32,
+    idx: usize,
+}
+
+impl NodeId {
+    /// Get the position of the node in its tree, which is also its position in the vectors
+    /// returned by `ArenaTree::fold_up` and `ArenaTree::fold_down`
+    pub fn index(self) -> usize {
+        self.idx
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.idx)
+    }
+}
+
+/// Error returned when a requested node does not exist in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeDoesNotExist {
+    /// Id of the missing node
+    pub id: NodeId,
+}
+
+impl fmt::Display for NodeDoesNotExist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} does not exist in the tree", self.id)
+    }
+}
+
+impl Error for NodeDoesNotExist {}
+
+/// Error type for changes to the shape of an ArenaTree<T>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeError {
+    /// Raised when a node does not exist in the tree, e.g. because it was removed
+    NodeDoesNotExist(NodeId),
+    /// Raised when making child the child of parent would create a cycle, i.e. when parent is
+    /// child itself or one of its descendants
+    WouldCreateCycle {
+        /// Id of the node being moved
+        child: NodeId,
+        /// Id of the requested new parent
+        parent: NodeId,
+    },
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::NodeDoesNotExist(id) => write!(f, "node {} does not exist in the tree", id),
+            TreeError::WouldCreateCycle { child, parent } => write!(
+                f,
+                "cannot move node {} under node {} since it would create a cycle",
+                child, parent
+            ),
+        }
+    }
+}
+
+impl Error for TreeError {}
+
+impl From<NodeDoesNotExist> for TreeError {
+    fn from(e: NodeDoesNotExist) -> Self {
+        TreeError::NodeDoesNotExist(e.id)
+    }
+}
+
+/// Tag for the next tree created, so that ids from different trees never compare equal
+static NEXT_TREE_ID: AtomicU32 = AtomicU32::new(0);
 
 /// A tree with nodes of types Node<T> all stored in the same vector
 ///
+/// Removed nodes leave a tombstone behind, so the id of a node never changes and ids are never
+/// reused.
+///
 /// # Examples
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ArenaTree<T>
 where
     T: std::cmp::PartialEq,
 {
-    arena: Vec<Node<T>>,
+    id: u32,
+    arena: Vec<Option<Node<T>>>,
+    roots: Vec<NodeId>,
+    n_removed: usize,
+}
+
+impl<T> Default for ArenaTree<T>
+where
+    T: PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the value of a node, panicking if the node does not exist in the tree
+///
+/// # Examples
+/// ```
+/// use aoc_utils::tree::ArenaTree;
+///
+/// let mut tree = ArenaTree::new();
+/// let root = tree.add_node(1);
+/// tree[root] += 1;
+///
+/// assert_eq!(tree[root], 2);
+/// ```
+impl<T> Index<NodeId> for ArenaTree<T>
+where
+    T: PartialEq,
+{
+    type Output = T;
+
+    fn index(&self, idx: NodeId) -> &T {
+        match self.get_node(idx) {
+            Ok(node) => &node.value,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl<T> IndexMut<NodeId> for ArenaTree<T>
+where
+    T: PartialEq,
+{
+    fn index_mut(&mut self, idx: NodeId) -> &mut T {
+        match self.get_mut(idx) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+/// Tag of ids which were deserialized on their own and so belong to no tree
+#[cfg(feature = "serde")]
+const UNBOUND_TREE_ID: u32 = u32::MAX;
+
+/// Ids are serialized as their index alone, since the tag of a tree is only meaningful while
+/// the program runs. An id deserialized on its own belongs to no tree, while the ids inside a
+/// deserialized `ArenaTree` belong to that tree.
+#[cfg(feature = "serde")]
+impl serde::Serialize for NodeId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.idx as u64)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NodeId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let idx = usize::deserialize(deserializer)?;
+        Ok(NodeId {
+            tree: UNBOUND_TREE_ID,
+            idx,
+        })
+    }
+}
+
+/// A tree is serialized as its nodes, with `null` in place of removed ones so that the ids of
+/// the rest are unchanged, followed by its roots.
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for ArenaTree<T>
+where
+    T: PartialEq + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ArenaTree", 2)?;
+        state.serialize_field("nodes", &self.arena)?;
+        state.serialize_field("roots", &self.roots)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "ArenaTree")]
+struct SerializedTree<T>
+where
+    T: PartialEq,
+{
+    nodes: Vec<Option<Node<T>>>,
+    roots: Vec<NodeId>,
+}
+
+/// A deserialized tree is given a new tag, like a tree created with `ArenaTree::new`, and is
+/// checked with `ArenaTree::validate`, so that malformed input is an error rather than a tree
+/// which panics later.
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for ArenaTree<T>
+where
+    T: PartialEq + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SerializedTree { mut nodes, roots } = SerializedTree::deserialize(deserializer)?;
+        let mut tree = ArenaTree::new();
+        let bind = |idx: NodeId| NodeId {
+            tree: tree.id,
+            idx: idx.idx,
+        };
+        for node in nodes.iter_mut().flatten() {
+            node.id = bind(node.id);
+            node.parent = node.parent.map(bind);
+            for child in node.children.iter_mut() {
+                *child = bind(*child);
+            }
+        }
+        let roots = roots.into_iter().map(bind).collect();
+        tree.n_removed = nodes.iter().filter(|slot| slot.is_none()).count();
+        tree.arena = nodes;
+        tree.roots = roots;
+        tree.validate().map_err(serde::de::Error::custom)?;
+        Ok(tree)
+    }
 }
 
 /// A node in an ArenaTree<T>
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node<T>
 where
     T: PartialEq,
 {
-    idx: usize,
+    id: NodeId,
     /// Arbitrary value of type T held by Node<T>
     ///
     /// Must implement PartialEq
     pub value: T,
-    parent: Option<usize>,
-    children: Vec<usize>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
 }
 
 impl<T> Node<T>
 where
     T: PartialEq,
 {
-    fn new(idx: usize, value: T) -> Self {
+    fn new(id: NodeId, value: T) -> Self {
         Self {
-            idx,
+            id,
             value,
             parent: None,
             children: vec![],
         }
     }
 
-    /// Get the index of the node
-    pub fn idx(&self) -> usize {
-        self.idx
+    /// Get the id of the node
+    pub fn id(&self) -> NodeId {
+        self.id
     }
 
-    /// Get the index of the parent node
+    /// Get the id of the parent node
     ///
     /// Will return None if there is no parent node
-    pub fn parent(&self) -> Option<usize> {
+    pub fn parent(&self) -> Option<NodeId> {
         self.parent
     }
 
-    /// Get a vector if indices of the children nodes
-    pub fn children(&self) -> &Vec<usize> {
+    /// Get a vector of ids of the children nodes
+    pub fn children(&self) -> &Vec<NodeId> {
         &self.children
     }
 }
@@ -67,91 +287,2444 @@ where
 {
     /// Initialize a new tree with zero nodes
     pub fn new() -> ArenaTree<T> {
-        ArenaTree { arena: Vec::new() }
+        ArenaTree {
+            id: NEXT_TREE_ID.fetch_add(1, Ordering::Relaxed),
+            arena: Vec::new(),
+            roots: Vec::new(),
+            n_removed: 0,
+        }
     }
 
-    /// Get the number of nodes in the tree
+    /// Get the number of nodes in the tree, not counting removed nodes
     pub fn len(&self) -> usize {
-        self.arena.len()
+        self.arena.len() - self.n_removed
+    }
+
+    /// Check whether the tree has zero nodes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    /// Get node of index idx.
+    /// Iterate over every node in the tree in the order they were added, skipping removed nodes.
+    pub fn iter(&self) -> impl Iterator<Item = &Node<T>> {
+        self.arena.iter().flatten()
+    }
+
+    /// Get node with id idx.
+    ///
+    /// Will return error if the node does not exist in the tree, because it has been removed or
+    /// the id belongs to another tree
+    pub fn get_node(&self, idx: NodeId) -> Result<&Node<T>, NodeDoesNotExist> {
+        if idx.tree != self.id {
+            return Err(NodeDoesNotExist { id: idx });
+        }
+        self.arena
+            .get(idx.idx)
+            .and_then(Option::as_ref)
+            .ok_or(NodeDoesNotExist { id: idx })
+    }
+
+    /// Get a mutable reference to the value of the node with id idx.
     ///
-    /// Will return error if the node index does not exist in the tree
-    pub fn get_node(&self, idx: usize) -> Result<&Node<T>, NodeDoesNotExist> {
-        let n_nodes = self.arena.len();
+    /// Will return error if the node does not exist in the tree.
+    pub fn get_mut(&mut self, idx: NodeId) -> Result<&mut T, NodeDoesNotExist> {
+        Ok(&mut self.get_node_mut(idx)?.value)
+    }
 
-        if idx >= n_nodes {
-            return Err(NodeDoesNotExist {});
+    fn get_node_mut(&mut self, idx: NodeId) -> Result<&mut Node<T>, NodeDoesNotExist> {
+        if idx.tree != self.id {
+            return Err(NodeDoesNotExist { id: idx });
         }
-        Ok(self
-            .arena
-            .get(idx)
-            .expect("Arena has at least this many nodes"))
+        self.arena
+            .get_mut(idx.idx)
+            .and_then(Option::as_mut)
+            .ok_or(NodeDoesNotExist { id: idx })
+    }
+
+    /// Get a node which is linked to from another node, and so cannot have been removed
+    fn linked_node(&self, idx: NodeId) -> &Node<T> {
+        self.arena[idx.idx]
+            .as_ref()
+            .expect("Removed nodes are unlinked from the tree")
+    }
+
+    fn push_node(&mut self, value: T) -> NodeId {
+        let idx = NodeId {
+            tree: self.id,
+            idx: self.arena.len(),
+        };
+        self.arena.push(Some(Node::new(idx, value)));
+        idx
     }
 
-    /// Add node with value of type T to the tree and get the index back
-    pub fn add_node(&mut self, value: T) -> usize {
-        let idx = self.arena.len();
-        self.arena.push(Node::new(idx, value));
+    /// Add node with value of type T to the tree as a new root and get its id back
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        let idx = self.push_node(value);
+        self.roots.push(idx);
         idx
     }
 
     /// Register node at child_idx as child of node at parent_idx, and vice versa
     ///
-    /// Will return error if either index does not exist in the tree.
+    /// This is the same as `move_subtree`, so the child is first detached from any previous
+    /// parent. Will return error if either node does not exist in the tree or if the move
+    /// would create a cycle.
     pub fn register_parent_node(
         &mut self,
-        child_idx: usize,
-        parent_idx: usize,
-    ) -> Result<(), NodeDoesNotExist> {
-        let n_nodes = self.arena.len();
-        if (child_idx >= n_nodes) || (parent_idx >= n_nodes) {
-            return Err(NodeDoesNotExist {});
-        }
-        self.arena[child_idx].parent = Some(parent_idx);
-        if !self.arena[parent_idx].children.contains(&child_idx) {
-            self.arena[parent_idx].children.push(child_idx)
-        }
-        Ok(())
+        child_idx: NodeId,
+        parent_idx: NodeId,
+    ) -> Result<(), TreeError> {
+        self.move_subtree(child_idx, parent_idx)
     }
 
-    /// Add a child node with value of type T to tree with parent index parent_idx
+    /// Add a child node with value of type T to tree with parent id parent_idx
     ///
-    /// Will return an error if parent_idx does not exist.
+    /// Will return an error if the node parent_idx does not exist in the tree.
     pub fn add_child_node(
         &mut self,
-        parent_idx: usize,
+        parent_idx: NodeId,
         value: T,
-    ) -> Result<usize, NodeDoesNotExist> {
-        let n_nodes = self.arena.len();
-        if parent_idx >= n_nodes {
-            return Err(NodeDoesNotExist {});
-        };
-        let child_idx = self.add_node(value);
-        self.register_parent_node(child_idx, parent_idx)?;
+    ) -> Result<NodeId, NodeDoesNotExist> {
+        self.get_node(parent_idx)?;
+        let child_idx = self.push_node(value);
+        self.get_node_mut(child_idx)?.parent = Some(parent_idx);
+        self.get_node_mut(parent_idx)?.children.push(child_idx);
         Ok(child_idx)
     }
+
+    /// Move the node at idx, along with all of its descendants, to be the last child of the
+    /// node at new_parent_idx.
+    ///
+    /// The node is removed from the children of its previous parent, if it had one. Will return
+    /// error if either node does not exist in the tree, or if new_parent_idx is idx itself or
+    /// one of its descendants, since the move would then create a cycle.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::{ArenaTree, TreeError};
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node("/");
+    /// let a = tree.add_child_node(root, "a").unwrap();
+    /// let b = tree.add_child_node(root, "b").unwrap();
+    ///
+    /// tree.move_subtree(b, a).unwrap();
+    ///
+    /// assert_eq!(tree.get_node(root).unwrap().children(), &vec![a]);
+    /// assert_eq!(tree.get_node(b).unwrap().parent(), Some(a));
+    /// assert_eq!(
+    ///     tree.move_subtree(root, b),
+    ///     Err(TreeError::WouldCreateCycle { child: root, parent: b })
+    /// );
+    /// ```
+    pub fn move_subtree(&mut self, idx: NodeId, new_parent_idx: NodeId) -> Result<(), TreeError> {
+        let node = self.get_node(idx)?;
+        self.get_node(new_parent_idx)?;
+        if node.parent == Some(new_parent_idx) {
+            return Ok(());
+        }
+        if new_parent_idx == idx || self.ancestors(new_parent_idx)?.any(|n| n.id == idx) {
+            return Err(TreeError::WouldCreateCycle {
+                child: idx,
+                parent: new_parent_idx,
+            });
+        }
+        self.detach(idx)?;
+        self.roots.retain(|&root| root != idx);
+        self.get_node_mut(idx)?.parent = Some(new_parent_idx);
+        self.get_node_mut(new_parent_idx)?.children.push(idx);
+        Ok(())
+    }
+
+    /// Detach the node at idx, along with all of its descendants, from its parent so that it
+    /// becomes a root.
+    ///
+    /// Does nothing if the node is already a root. Will return error if the node does not exist
+    /// in the tree.
+    pub fn detach(&mut self, idx: NodeId) -> Result<(), NodeDoesNotExist> {
+        if let Some(parent_idx) = self.get_node_mut(idx)?.parent.take() {
+            self.get_node_mut(parent_idx)?
+                .children
+                .retain(|&child| child != idx);
+            self.roots.push(idx);
+        }
+        Ok(())
+    }
+
+    /// Remove the node at idx from the tree and get its value back.
+    ///
+    /// The children of the node take its place among its parent's children, or among the roots
+    /// if it had no parent. Will return error if the node does not exist in the tree.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node(0);
+    /// let one = tree.add_child_node(root, 1).unwrap();
+    /// let two = tree.add_child_node(one, 2).unwrap();
+    ///
+    /// assert_eq!(tree.remove_node(one).unwrap(), 1);
+    /// assert_eq!(tree.get_node(two).unwrap().parent(), Some(root));
+    /// assert!(tree.get_node(one).is_err());
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn remove_node(&mut self, idx: NodeId) -> Result<T, NodeDoesNotExist> {
+        self.get_node(idx)?;
+        let node = self.arena[idx.idx].take().expect("Node exists");
+        self.n_removed += 1;
+        for &child in &node.children {
+            self.arena[child.idx]
+                .as_mut()
+                .expect("Removed nodes are unlinked from the tree")
+                .parent = node.parent;
+        }
+        let siblings = match node.parent {
+            Some(parent_idx) => &mut self.get_node_mut(parent_idx)?.children,
+            None => &mut self.roots,
+        };
+        let position = siblings
+            .iter()
+            .position(|&sibling| sibling == idx)
+            .expect("Node is among its parent's children or the roots");
+        siblings.splice(position..=position, node.children);
+        Ok(node.value)
+    }
+
+    /// Remove the node at idx and all of its descendants from the tree, and get their values
+    /// back in pre-order.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn remove_subtree(&mut self, idx: NodeId) -> Result<Vec<T>, NodeDoesNotExist> {
+        self.detach(idx)?;
+        self.roots.retain(|&root| root != idx);
+        let ids: Vec<NodeId> = self.pre_order(idx)?.map(|node| node.id).collect();
+        self.n_removed += ids.len();
+        Ok(ids
+            .into_iter()
+            .map(|id| self.arena[id.idx].take().expect("Node exists").value)
+            .collect())
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Description of a broken invariant, returned by `ArenaTree::validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// Raised when a node is stored in the slot for another id
+    WrongId {
+        /// Id of the slot
+        expected: NodeId,
+        /// Id recorded in the node
+        found: NodeId,
+    },
+    /// Raised when a node links to a parent or child which does not exist in the tree
+    DanglingLink {
+        /// Id of the node holding the link
+        from: NodeId,
+        /// Id of the missing node
+        to: NodeId,
+    },
+    /// Raised when a node does not appear exactly once among the children of its parent, or
+    /// appears among the children of a node which is not its parent
+    ParentChildMismatch {
+        /// Id of the parent
+        parent: NodeId,
+        /// Id of the child
+        child: NodeId,
+    },
+    /// Raised when a node without a parent does not appear exactly once among the roots, or a
+    /// node with a parent appears among them
+    RootMismatch(NodeId),
+    /// Raised when a node cannot be reached from any root, because it is part of a cycle
+    Unreachable(NodeId),
+    /// Raised when the number of nodes does not agree with the number of removed nodes
+    WrongLength {
+        /// Number of nodes actually stored
+        expected: usize,
+        /// Number of nodes reported by `len`
+        found: usize,
+    },
+}
 
-    #[test]
-    fn create_arena_tree_of_depth_three() {
-        let mut tree: ArenaTree<u32> = ArenaTree::new();
-        let idx0 = tree.add_node(10);
-        let idx1 = tree.add_node(20);
-        let idx2 = tree.add_node(21);
-        tree.register_parent_node(idx1, idx0).unwrap();
-        tree.register_parent_node(idx2, idx0).unwrap();
-        let idx3 = tree.add_child_node(idx1, 30).unwrap();
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::WrongId { expected, found } => {
+                write!(
+                    f,
+                    "node {} is stored in the slot for node {}",
+                    found, expected
+                )
+            }
+            InvariantViolation::DanglingLink { from, to } => {
+                write!(f, "node {} links to missing node {}", from, to)
+            }
+            InvariantViolation::ParentChildMismatch { parent, child } => write!(
+                f,
+                "node {} and its parent {} disagree about their link",
+                child, parent
+            ),
+            InvariantViolation::RootMismatch(id) => {
+                write!(f, "node {} disagrees with the list of roots", id)
+            }
+            InvariantViolation::Unreachable(id) => {
+                write!(f, "node {} cannot be reached from any root", id)
+            }
+            InvariantViolation::WrongLength { expected, found } => {
+                write!(f, "tree holds {} nodes but reports {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for InvariantViolation {}
+
+impl<T> ArenaTree<T>
+where
+    T: PartialEq,
+{
+    /// Move every node of other into this tree, keeping their shape, so that the roots of other
+    /// become roots of this tree too.
+    ///
+    /// Returns a map from the id of every node in other to its new id in this tree.
+    pub fn merge(&mut self, other: ArenaTree<T>) -> HashMap<NodeId, NodeId> {
+        self.merge_nodes(other, None)
+    }
+
+    /// Move every node of other into this tree, keeping their shape, so that the roots of other
+    /// become the last children of the node at parent_idx.
+    ///
+    /// Returns a map from the id of every node in other to its new id in this tree. Will return
+    /// error, leaving both trees unchanged, if the node parent_idx does not exist in the tree.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node("/");
+    /// let mut other = ArenaTree::new();
+    /// let a = other.add_node("a");
+    /// let b = other.add_child_node(a, "b").unwrap();
+    ///
+    /// let ids = tree.merge_under(root, other).unwrap();
+    ///
+    /// assert_eq!(tree.get_node(root).unwrap().children(), &vec![ids[&a]]);
+    /// assert_eq!(tree[ids[&b]], "b");
+    /// assert_eq!(tree.roots().count(), 1);
+    /// ```
+    pub fn merge_under(
+        &mut self,
+        parent_idx: NodeId,
+        other: ArenaTree<T>,
+    ) -> Result<HashMap<NodeId, NodeId>, NodeDoesNotExist> {
+        self.get_node(parent_idx)?;
+        Ok(self.merge_nodes(other, Some(parent_idx)))
+    }
+
+    fn merge_nodes(
+        &mut self,
+        other: ArenaTree<T>,
+        parent_idx: Option<NodeId>,
+    ) -> HashMap<NodeId, NodeId> {
+        let mut ids = HashMap::new();
+        for (i, node) in other.iter().enumerate() {
+            let new_id = NodeId {
+                tree: self.id,
+                idx: self.arena.len() + i,
+            };
+            ids.insert(node.id, new_id);
+        }
+        for node in other.arena.into_iter().flatten() {
+            self.arena.push(Some(Node {
+                id: ids[&node.id],
+                value: node.value,
+                parent: node.parent.map(|parent| ids[&parent]).or(parent_idx),
+                children: node.children.iter().map(|child| ids[child]).collect(),
+            }));
+        }
+        let new_roots = other.roots.iter().map(|root| ids[root]);
+        match parent_idx {
+            Some(parent_idx) => self.arena[parent_idx.idx]
+                .as_mut()
+                .expect("Parent exists")
+                .children
+                .extend(new_roots),
+            None => self.roots.extend(new_roots),
+        }
+        ids
+    }
+
+    /// Check that the links between nodes are consistent: every node is stored under its own
+    /// id, every non-root appears exactly once among the children of its parent, the roots are
+    /// exactly the nodes without a parent, and every node can be reached from a root.
+    ///
+    /// The methods of `ArenaTree` always keep these invariants, so this is mostly useful in
+    /// tests. Will return error describing the first broken invariant found.
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let n_stored = self.iter().count();
+        if n_stored != self.len() {
+            return Err(InvariantViolation::WrongLength {
+                expected: n_stored,
+                found: self.len(),
+            });
+        }
+        let mut times_listed = vec![0; self.arena.len()];
+        for (idx, slot) in self.arena.iter().enumerate() {
+            let Some(node) = slot else { continue };
+            let expected = NodeId { tree: self.id, idx };
+            if node.id != expected {
+                return Err(InvariantViolation::WrongId {
+                    expected,
+                    found: node.id,
+                });
+            }
+            for &child in &node.children {
+                let child_node =
+                    self.get_node(child)
+                        .map_err(|_| InvariantViolation::DanglingLink {
+                            from: node.id,
+                            to: child,
+                        })?;
+                if child_node.parent != Some(node.id) {
+                    return Err(InvariantViolation::ParentChildMismatch {
+                        parent: node.id,
+                        child,
+                    });
+                }
+                times_listed[child.idx] += 1;
+            }
+        }
+        let mut times_root = vec![0; self.arena.len()];
+        for &root in &self.roots {
+            match self.get_node(root) {
+                Ok(node) if node.parent.is_none() => times_root[root.idx] += 1,
+                _ => return Err(InvariantViolation::RootMismatch(root)),
+            }
+        }
+        for node in self.iter() {
+            match node.parent {
+                Some(parent) => {
+                    if self.get_node(parent).is_err() {
+                        return Err(InvariantViolation::DanglingLink {
+                            from: node.id,
+                            to: parent,
+                        });
+                    }
+                    if times_listed[node.id.idx] != 1 {
+                        return Err(InvariantViolation::ParentChildMismatch {
+                            parent,
+                            child: node.id,
+                        });
+                    }
+                }
+                None => {
+                    if times_root[node.id.idx] != 1 {
+                        return Err(InvariantViolation::RootMismatch(node.id));
+                    }
+                }
+            }
+        }
+        // With the links consistent, a node can only be missed here if it is part of a cycle
+        let mut reached = vec![false; self.arena.len()];
+        for root in self.roots() {
+            for node in self.pre_order(root.id).expect("Root id is valid") {
+                reached[node.id.idx] = true;
+            }
+        }
+        match self.iter().find(|node| !reached[node.id.idx]) {
+            Some(node) => Err(InvariantViolation::Unreachable(node.id)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> ArenaTree<T>
+where
+    T: PartialEq,
+{
+    /// Iterate over the subtree rooted at idx in pre-order, i.e. each node before its children.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn pre_order(&self, idx: NodeId) -> Result<PreOrder<'_, T>, NodeDoesNotExist> {
+        self.get_node(idx)?;
+        Ok(PreOrder {
+            tree: self,
+            stack: vec![idx],
+        })
+    }
+
+    /// Iterate over the subtree rooted at idx in post-order, i.e. each node after its children.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn post_order(&self, idx: NodeId) -> Result<PostOrder<'_, T>, NodeDoesNotExist> {
+        self.get_node(idx)?;
+        Ok(PostOrder {
+            tree: self,
+            stack: vec![(idx, false)],
+        })
+    }
+
+    /// Iterate over the subtree rooted at idx breadth-first, i.e. level by level.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn breadth_first(&self, idx: NodeId) -> Result<BreadthFirst<'_, T>, NodeDoesNotExist> {
+        self.get_node(idx)?;
+        Ok(BreadthFirst {
+            tree: self,
+            queue: VecDeque::from([idx]),
+        })
+    }
+
+    /// Iterate over the ancestors of the node at idx, starting with its parent.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn ancestors(&self, idx: NodeId) -> Result<Ancestors<'_, T>, NodeDoesNotExist> {
+        Ok(Ancestors {
+            tree: self,
+            next: self.get_node(idx)?.parent,
+        })
+    }
+
+    /// Iterate over the descendants of the node at idx in pre-order, not including the node
+    /// itself.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn descendants(&self, idx: NodeId) -> Result<PreOrder<'_, T>, NodeDoesNotExist> {
+        Ok(PreOrder {
+            tree: self,
+            stack: self.get_node(idx)?.children.iter().rev().copied().collect(),
+        })
+    }
+}
+
+impl<T> ArenaTree<T>
+where
+    T: PartialEq,
+{
+    /// Iterate over the roots of the tree, i.e. the nodes without a parent, in the order they
+    /// became roots.
+    ///
+    /// A tree with more than one root is a forest, and every node belongs to the tree under
+    /// exactly one of the roots.
+    pub fn roots(&self) -> impl Iterator<Item = &Node<T>> {
+        self.roots.iter().map(|&root| self.linked_node(root))
+    }
+
+    /// Get the id of the root of the tree which the node at idx belongs to.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn root_of(&self, idx: NodeId) -> Result<NodeId, NodeDoesNotExist> {
+        Ok(self.ancestors(idx)?.last().map_or(idx, |root| root.id))
+    }
+
+    /// Compute a value for every node from the node itself and the values already computed for
+    /// its children, in a single post-order pass.
+    ///
+    /// Returns a vector of the computed values indexed by `NodeId::index`, with `None` at the
+    /// index of every removed node.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node(1);
+    /// let child = tree.add_child_node(root, 2).unwrap();
+    /// tree.add_child_node(child, 3).unwrap();
+    ///
+    /// let subtree_sums = tree.fold_up(|node, children: &[&u32]| {
+    ///     node.value + children.iter().copied().sum::<u32>()
+    /// });
+    ///
+    /// assert_eq!(subtree_sums, vec![Some(6), Some(5), Some(3)]);
+    /// ```
+    pub fn fold_up<R, F>(&self, mut f: F) -> Vec<Option<R>>
+    where
+        F: FnMut(&Node<T>, &[&R]) -> R,
+    {
+        let mut results: Vec<Option<R>> = (0..self.arena.len()).map(|_| None).collect();
+        for root in self.roots() {
+            for node in self.post_order(root.id).expect("Root id is valid") {
+                let child_results: Vec<&R> = node
+                    .children
+                    .iter()
+                    .map(|&child| {
+                        results[child.idx]
+                            .as_ref()
+                            .expect("Children are visited before their parent")
+                    })
+                    .collect();
+                let result = f(node, &child_results);
+                results[node.id.idx] = Some(result);
+            }
+        }
+        results
+    }
+
+    /// Compute a value for every node from the node itself and the value already computed for
+    /// its parent, which is `None` for root nodes, in a single pre-order pass.
+    ///
+    /// Returns a vector of the computed values indexed by `NodeId::index`, with `None` at the
+    /// index of every removed node.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node("");
+    /// let a = tree.add_child_node(root, "a").unwrap();
+    /// tree.add_child_node(a, "b").unwrap();
+    ///
+    /// let depths = tree.fold_down(|_, parent: Option<&usize>| parent.map_or(0, |d| d + 1));
+    /// let paths = tree.fold_down(|node, parent: Option<&String>| match parent {
+    ///     None => String::from("/"),
+    ///     Some(path) => format!("{}{}/", path, node.value),
+    /// });
+    ///
+    /// assert_eq!(depths, vec![Some(0), Some(1), Some(2)]);
+    /// assert_eq!(paths[2].as_deref(), Some("/a/b/"));
+    /// ```
+    pub fn fold_down<R, F>(&self, mut f: F) -> Vec<Option<R>>
+    where
+        F: FnMut(&Node<T>, Option<&R>) -> R,
+    {
+        let mut results: Vec<Option<R>> = (0..self.arena.len()).map(|_| None).collect();
+        for root in self.roots() {
+            for node in self.pre_order(root.id).expect("Root id is valid") {
+                let parent_result = node.parent.map(|parent| {
+                    results[parent.idx]
+                        .as_ref()
+                        .expect("Parents are visited before their children")
+                });
+                let result = f(node, parent_result);
+                results[node.id.idx] = Some(result);
+            }
+        }
+        results
+    }
+}
+
+impl<T> ArenaTree<T>
+where
+    T: PartialEq,
+{
+    /// Get the depth of the node at idx, i.e. the number of edges between it and its root.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn depth(&self, idx: NodeId) -> Result<usize, NodeDoesNotExist> {
+        Ok(self.ancestors(idx)?.count())
+    }
+
+    /// Get the height of the subtree rooted at idx, i.e. the number of edges on the longest
+    /// path down from it to a leaf.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn height(&self, idx: NodeId) -> Result<usize, NodeDoesNotExist> {
+        let mut heights = HashMap::new();
+        for node in self.post_order(idx)? {
+            let height = node
+                .children
+                .iter()
+                .map(|child| heights[child] + 1)
+                .max()
+                .unwrap_or(0);
+            heights.insert(node.id, height);
+        }
+        Ok(heights[&idx])
+    }
+
+    /// Get the id of the lowest node which is an ancestor of both a and b, where every node
+    /// counts as an ancestor of itself.
+    ///
+    /// Returns `None` if the nodes are in different trees of a forest. This walks up from both
+    /// nodes on every call; use `ancestor_table` for many queries on the same tree. Will return
+    /// error if either node does not exist in the tree.
+    pub fn lowest_common_ancestor(
+        &self,
+        a: NodeId,
+        b: NodeId,
+    ) -> Result<Option<NodeId>, NodeDoesNotExist> {
+        let mut a_ancestors: HashSet<NodeId> = self.ancestors(a)?.map(|node| node.id).collect();
+        a_ancestors.insert(a);
+        if a_ancestors.contains(&b) {
+            return Ok(Some(b));
+        }
+        Ok(self
+            .ancestors(b)?
+            .map(|node| node.id)
+            .find(|idx| a_ancestors.contains(idx)))
+    }
 
-        assert_eq!((idx0, idx1, idx2, idx3), (0, 1, 2, 3));
-        assert_eq!(tree.get_node(0).unwrap().value, 10);
-        assert_eq!(tree.get_node(1).unwrap().parent().unwrap(), 0);
-        assert_eq!(tree.get_node(3).unwrap().parent().unwrap(), 1);
-        assert_eq!(tree.get_node(1).unwrap().children(), &vec![3]);
+    /// Get the number of edges on the path between a and b.
+    ///
+    /// Returns `None` if the nodes are in different trees of a forest. Will return error if
+    /// either node does not exist in the tree.
+    pub fn distance(&self, a: NodeId, b: NodeId) -> Result<Option<usize>, NodeDoesNotExist> {
+        Ok(match self.lowest_common_ancestor(a, b)? {
+            Some(lca) => {
+                let lca_depth = self.depth(lca)?;
+                Some(self.depth(a)? + self.depth(b)? - 2 * lca_depth)
+            }
+            None => None,
+        })
+    }
+
+    /// Precompute the depth and the ancestors at every power of two distance of every node, so
+    /// that ancestor, lowest common ancestor and distance queries take logarithmic time.
+    ///
+    /// The table borrows the tree, so the tree cannot change while the table is in use.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let com = tree.add_node("COM");
+    /// let b = tree.add_child_node(com, "B").unwrap();
+    /// let you = tree.add_child_node(b, "YOU").unwrap();
+    /// let c = tree.add_child_node(com, "C").unwrap();
+    /// let san = tree.add_child_node(c, "SAN").unwrap();
+    ///
+    /// let table = tree.ancestor_table();
+    ///
+    /// assert_eq!(table.depth(san).unwrap(), 2);
+    /// assert_eq!(table.lowest_common_ancestor(you, san).unwrap(), Some(com));
+    /// assert_eq!(table.distance(you, san).unwrap(), Some(4));
+    /// assert_eq!(table.ancestor(you, 2).unwrap(), Some(com));
+    /// ```
+    pub fn ancestor_table(&self) -> AncestorTable<'_, T> {
+        let depths = self.fold_down(|_, parent: Option<&usize>| parent.map_or(0, |d| d + 1));
+        let max_depth = depths.iter().flatten().copied().max().unwrap_or(0);
+        let n_levels = (usize::BITS - max_depth.leading_zeros()).max(1) as usize;
+        // Roots, and removed nodes, are their own parents so that jumps stop at the root
+        let mut up = vec![self
+            .arena
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| {
+                node.as_ref()
+                    .and_then(|node| node.parent)
+                    .map_or(idx, |parent| parent.idx)
+            })
+            .collect::<Vec<usize>>()];
+        for level in 1..n_levels {
+            let previous = &up[level - 1];
+            let next = previous.iter().map(|&mid| previous[mid]).collect();
+            up.push(next);
+        }
+        AncestorTable {
+            tree: self,
+            depths,
+            up,
+        }
+    }
+}
+
+/// Precomputed ancestors of every node in an ArenaTree<T>, for fast repeated queries
+///
+/// Created by `ArenaTree::ancestor_table`.
+pub struct AncestorTable<'a, T>
+where
+    T: PartialEq,
+{
+    tree: &'a ArenaTree<T>,
+    depths: Vec<Option<usize>>,
+    // up[k][idx] is the ancestor 2^k levels above idx, or the root if there is none
+    up: Vec<Vec<usize>>,
+}
+
+impl<'a, T> AncestorTable<'a, T>
+where
+    T: PartialEq,
+{
+    /// Get the depth of the node at idx in constant time.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn depth(&self, idx: NodeId) -> Result<usize, NodeDoesNotExist> {
+        self.tree.get_node(idx)?;
+        Ok(self.depths[idx.idx].expect("Every node has a depth"))
+    }
+
+    fn jump(&self, idx: NodeId, k: usize) -> NodeId {
+        let mut i = idx.idx;
+        for (level, up) in self.up.iter().enumerate() {
+            if k >> level & 1 == 1 {
+                i = up[i];
+            }
+        }
+        NodeId { idx: i, ..idx }
+    }
+
+    /// Get the id of the ancestor k levels above the node at idx, which is the node itself
+    /// when k is zero.
+    ///
+    /// Returns `None` if k is greater than the depth of the node. Will return error if the node
+    /// does not exist in the tree.
+    pub fn ancestor(&self, idx: NodeId, k: usize) -> Result<Option<NodeId>, NodeDoesNotExist> {
+        if k > self.depth(idx)? {
+            return Ok(None);
+        }
+        Ok(Some(self.jump(idx, k)))
+    }
+
+    /// Get the id of the lowest common ancestor of a and b in logarithmic time. See
+    /// `ArenaTree::lowest_common_ancestor`.
+    pub fn lowest_common_ancestor(
+        &self,
+        a: NodeId,
+        b: NodeId,
+    ) -> Result<Option<NodeId>, NodeDoesNotExist> {
+        let (depth_a, depth_b) = (self.depth(a)?, self.depth(b)?);
+        let (mut a, mut b) = if depth_a > depth_b {
+            (self.jump(a, depth_a - depth_b), b)
+        } else {
+            (a, self.jump(b, depth_b - depth_a))
+        };
+        if a == b {
+            return Ok(Some(a));
+        }
+        for up in self.up.iter().rev() {
+            if up[a.idx] != up[b.idx] {
+                a.idx = up[a.idx];
+                b.idx = up[b.idx];
+            }
+        }
+        // If the nodes are in different trees, both have now stopped at their roots
+        let (parent_a, parent_b) = (self.up[0][a.idx], self.up[0][b.idx]);
+        Ok((parent_a == parent_b).then_some(NodeId { idx: parent_a, ..a }))
+    }
+
+    /// Get the number of edges on the path between a and b in logarithmic time. See
+    /// `ArenaTree::distance`.
+    pub fn distance(&self, a: NodeId, b: NodeId) -> Result<Option<usize>, NodeDoesNotExist> {
+        Ok(match self.lowest_common_ancestor(a, b)? {
+            Some(lca) => Some(self.depth(a)? + self.depth(b)? - 2 * self.depth(lca)?),
+            None => None,
+        })
+    }
+
+    /// Get the tree the table was built from
+    pub fn tree(&self) -> &'a ArenaTree<T> {
+        self.tree
+    }
+}
+
+/// A value with a key which identifies it among its siblings in a tree, e.g. the name of a file
+/// in a directory.
+pub trait Keyed {
+    /// Type of the key
+    type Key: Eq + Hash + Clone;
+
+    /// Get the key of the value
+    fn key(&self) -> &Self::Key;
+}
+
+impl<T> ArenaTree<T>
+where
+    T: PartialEq,
+{
+    /// Find the first child of the node at idx for which pred returns true.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn find_child<P>(
+        &self,
+        idx: NodeId,
+        mut pred: P,
+    ) -> Result<Option<&Node<T>>, NodeDoesNotExist>
+    where
+        P: FnMut(&Node<T>) -> bool,
+    {
+        Ok(self
+            .get_node(idx)?
+            .children
+            .iter()
+            .map(|&child| self.linked_node(child))
+            .find(|&node| pred(node)))
+    }
+
+    /// Find the first child of the node at idx whose value has the given key.
+    ///
+    /// This is a linear scan over the children; use a `KeyedArenaTree` for constant time
+    /// lookups. Will return error if the node does not exist in the tree.
+    pub fn find_child_by_key<Q>(
+        &self,
+        idx: NodeId,
+        key: &Q,
+    ) -> Result<Option<&Node<T>>, NodeDoesNotExist>
+    where
+        T: Keyed,
+        T::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.find_child(idx, |node| node.value.key().borrow() == key)
+    }
+
+    /// Follow a path of keys down the tree from the node at idx, and get the node it leads to.
+    ///
+    /// Returns `None` if some key along the path has no matching child. Will return error if
+    /// the node does not exist in the tree.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::{ArenaTree, Keyed};
+    ///
+    /// #[derive(PartialEq)]
+    /// struct Dir(String);
+    ///
+    /// impl Keyed for Dir {
+    ///     type Key = String;
+    ///
+    ///     fn key(&self) -> &String {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node(Dir("/".to_string()));
+    /// let a = tree.add_child_node(root, Dir("a".to_string())).unwrap();
+    /// let e = tree.add_child_node(a, Dir("e".to_string())).unwrap();
+    ///
+    /// assert_eq!(tree.resolve_path(root, ["a", "e"]).unwrap().map(|node| node.id()), Some(e));
+    /// assert!(tree.resolve_path(root, ["e"]).unwrap().is_none());
+    /// ```
+    pub fn resolve_path<'p, Q, I>(
+        &self,
+        idx: NodeId,
+        path: I,
+    ) -> Result<Option<&Node<T>>, NodeDoesNotExist>
+    where
+        T: Keyed,
+        T::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'p,
+        I: IntoIterator<Item = &'p Q>,
+    {
+        let mut node = self.get_node(idx)?;
+        for key in path {
+            match self.find_child_by_key(node.id, key)? {
+                Some(child) => node = child,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(node))
+    }
+}
+
+/// An ArenaTree<T> which also keeps a hash map from key to child id for every node, so that
+/// children can be looked up by key in constant time.
+///
+/// All of the read-only methods of `ArenaTree` are available through `Deref`, while methods
+/// which change the shape of the tree are reimplemented here to keep the maps up to date. If
+/// several children of a node share a key, lookups find the first of them, just as
+/// `ArenaTree::find_child_by_key` does.
+/// Values can be read with `tree[id]` but there is no `IndexMut`, since changing a value could
+/// change its key.
+///
+/// # Examples
+/// ```
+/// use aoc_utils::tree::{Keyed, KeyedArenaTree};
+///
+/// #[derive(PartialEq)]
+/// struct Dir<'a>(&'a str);
+///
+/// impl<'a> Keyed for Dir<'a> {
+///     type Key = &'a str;
+///
+///     fn key(&self) -> &&'a str {
+///         &self.0
+///     }
+/// }
+///
+/// let mut tree = KeyedArenaTree::new();
+/// let root = tree.add_node(Dir("/"));
+/// let a = tree.add_child_node(root, Dir("a")).unwrap();
+///
+/// assert_eq!(tree.find_child_by_key(root, "a").unwrap().map(|node| node.id()), Some(a));
+/// assert_eq!(tree.pre_order(root).unwrap().count(), 2);
+/// ```
+#[derive(Debug)]
+pub struct KeyedArenaTree<T>
+where
+    T: Keyed + PartialEq,
+{
+    tree: ArenaTree<T>,
+    child_index: Vec<HashMap<T::Key, NodeId>>,
+}
+
+impl<T> Default for KeyedArenaTree<T>
+where
+    T: Keyed + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for KeyedArenaTree<T>
+where
+    T: Keyed + PartialEq,
+{
+    type Target = ArenaTree<T>;
+
+    fn deref(&self) -> &ArenaTree<T> {
+        &self.tree
+    }
+}
+
+impl<T> KeyedArenaTree<T>
+where
+    T: Keyed + PartialEq,
+{
+    /// Initialize a new tree with zero nodes
+    pub fn new() -> KeyedArenaTree<T> {
+        KeyedArenaTree {
+            tree: ArenaTree::new(),
+            child_index: Vec::new(),
+        }
+    }
+
+    /// Get the underlying ArenaTree<T>, dropping the child index
+    pub fn into_inner(self) -> ArenaTree<T> {
+        self.tree
+    }
+
+    /// Add node with value of type T to the tree and get its id back
+    pub fn add_node(&mut self, value: T) -> NodeId {
+        self.child_index.push(HashMap::new());
+        self.tree.add_node(value)
+    }
+
+    /// Add a child node with value of type T to tree with parent id parent_idx
+    ///
+    /// Will return an error if the node parent_idx does not exist in the tree.
+    pub fn add_child_node(
+        &mut self,
+        parent_idx: NodeId,
+        value: T,
+    ) -> Result<NodeId, NodeDoesNotExist> {
+        let key = value.key().clone();
+        let child_idx = self.tree.add_child_node(parent_idx, value)?;
+        self.child_index.push(HashMap::new());
+        // New children go last, so they only become the first with their key if there is none
+        self.child_index[parent_idx.idx]
+            .entry(key)
+            .or_insert(child_idx);
+        Ok(child_idx)
+    }
+
+    /// Move the node at idx, along with all of its descendants, to be the last child of the
+    /// node at new_parent_idx. See `ArenaTree::move_subtree`.
+    pub fn move_subtree(&mut self, idx: NodeId, new_parent_idx: NodeId) -> Result<(), TreeError> {
+        let old_parent_idx = self.tree.get_node(idx)?.parent;
+        self.tree.move_subtree(idx, new_parent_idx)?;
+        if old_parent_idx != Some(new_parent_idx) {
+            let key = self.key_of(idx);
+            if let Some(old_parent_idx) = old_parent_idx {
+                self.reindex_key(old_parent_idx, &key);
+            }
+            self.child_index[new_parent_idx.idx]
+                .entry(key)
+                .or_insert(idx);
+        }
+        Ok(())
+    }
+
+    /// Detach the node at idx, along with all of its descendants, from its parent so that it
+    /// becomes a root. See `ArenaTree::detach`.
+    pub fn detach(&mut self, idx: NodeId) -> Result<(), NodeDoesNotExist> {
+        let parent_idx = self.tree.get_node(idx)?.parent;
+        self.tree.detach(idx)?;
+        if let Some(parent_idx) = parent_idx {
+            let key = self.key_of(idx);
+            self.reindex_key(parent_idx, &key);
+        }
+        Ok(())
+    }
+
+    /// Remove the node at idx from the tree and get its value back. See
+    /// `ArenaTree::remove_node`.
+    pub fn remove_node(&mut self, idx: NodeId) -> Result<T, NodeDoesNotExist> {
+        let parent_idx = self.tree.get_node(idx)?.parent;
+        let value = self.tree.remove_node(idx)?;
+        self.child_index[idx.idx].clear();
+        if let Some(parent_idx) = parent_idx {
+            // The removed node's children are spliced in among their new siblings, so which of
+            // them comes first for a given key may have changed.
+            self.reindex(parent_idx);
+        }
+        Ok(value)
+    }
+
+    /// Remove the node at idx and all of its descendants from the tree, and get their values
+    /// back in pre-order. See `ArenaTree::remove_subtree`.
+    pub fn remove_subtree(&mut self, idx: NodeId) -> Result<Vec<T>, NodeDoesNotExist> {
+        self.detach(idx)?;
+        let ids: Vec<NodeId> = self.tree.pre_order(idx)?.map(|node| node.id).collect();
+        for id in ids {
+            self.child_index[id.idx].clear();
+        }
+        self.tree.remove_subtree(idx)
+    }
+
+    /// Move every node of other into this tree so that the roots of other become roots of this
+    /// tree too. See `ArenaTree::merge`.
+    pub fn merge(&mut self, other: ArenaTree<T>) -> HashMap<NodeId, NodeId> {
+        let ids = self.tree.merge(other);
+        self.index_merged(&ids);
+        ids
+    }
+
+    /// Move every node of other into this tree so that the roots of other become the last
+    /// children of the node at parent_idx. See `ArenaTree::merge_under`.
+    pub fn merge_under(
+        &mut self,
+        parent_idx: NodeId,
+        other: ArenaTree<T>,
+    ) -> Result<HashMap<NodeId, NodeId>, NodeDoesNotExist> {
+        let ids = self.tree.merge_under(parent_idx, other)?;
+        self.index_merged(&ids);
+        self.reindex(parent_idx);
+        Ok(ids)
+    }
+
+    fn index_merged(&mut self, ids: &HashMap<NodeId, NodeId>) {
+        self.child_index
+            .resize_with(self.tree.arena.len(), HashMap::new);
+        for &id in ids.values() {
+            self.reindex(id);
+        }
+    }
+
+    /// Find the child of the node at idx whose value has the given key in constant time.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn find_child_by_key<Q>(
+        &self,
+        idx: NodeId,
+        key: &Q,
+    ) -> Result<Option<&Node<T>>, NodeDoesNotExist>
+    where
+        T::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.tree.get_node(idx)?;
+        Ok(self.child_index[idx.idx]
+            .get(key)
+            .map(|&child| self.tree.linked_node(child)))
+    }
+
+    /// Follow a path of keys down the tree from the node at idx, and get the node it leads to,
+    /// with a constant time lookup for each key. See `ArenaTree::resolve_path`.
+    pub fn resolve_path<'p, Q, I>(
+        &self,
+        idx: NodeId,
+        path: I,
+    ) -> Result<Option<&Node<T>>, NodeDoesNotExist>
+    where
+        T::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'p,
+        I: IntoIterator<Item = &'p Q>,
+    {
+        let mut node = self.tree.get_node(idx)?;
+        for key in path {
+            match self.find_child_by_key(node.id, key)? {
+                Some(child) => node = child,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(node))
+    }
+
+    fn key_of(&self, idx: NodeId) -> T::Key {
+        self.tree.linked_node(idx).value.key().clone()
+    }
+
+    /// Point key at the first child of parent_idx which has it, if there still is one
+    fn reindex_key(&mut self, parent_idx: NodeId, key: &T::Key) {
+        match self.tree.find_child_by_key(parent_idx, key) {
+            Ok(Some(child)) => {
+                let child_idx = child.id;
+                self.child_index[parent_idx.idx].insert(key.clone(), child_idx);
+            }
+            _ => {
+                self.child_index[parent_idx.idx].remove(key);
+            }
+        }
+    }
+
+    /// Rebuild the whole child index of parent_idx
+    fn reindex(&mut self, parent_idx: NodeId) {
+        let mut index = HashMap::new();
+        for &child in &self.tree.linked_node(parent_idx).children {
+            index.entry(self.key_of(child)).or_insert(child);
+        }
+        self.child_index[parent_idx.idx] = index;
+    }
+}
+
+impl<T> ArenaTree<T>
+where
+    T: PartialEq + fmt::Display,
+{
+    /// Render the tree as indented lines joined by box-drawing characters, like the output of
+    /// the `tree` command, with one block per root.
+    pub fn render(&self) -> String {
+        self.render_with(|_| None::<String>)
+    }
+
+    /// Render the tree like `render`, adding the annotation returned by f, if any, in brackets
+    /// after each node.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node("/");
+    /// let a = tree.add_child_node(root, "a").unwrap();
+    /// tree.add_child_node(a, "e").unwrap();
+    /// tree.add_child_node(root, "b.txt").unwrap();
+    ///
+    /// let rendered = tree.render_with(|node| match node.children().len() {
+    ///     0 => None,
+    ///     n => Some(format!("{} children", n)),
+    /// });
+    ///
+    /// assert_eq!(
+    ///     rendered,
+    ///     "/ (2 children)\n├── a (1 children)\n│   └── e\n└── b.txt\n"
+    /// );
+    /// ```
+    pub fn render_with<A, F>(&self, mut f: F) -> String
+    where
+        A: fmt::Display,
+        F: FnMut(&Node<T>) -> Option<A>,
+    {
+        let mut res = String::new();
+        for root in self.roots() {
+            // Each entry holds the prefix for the node's own line and for its children's lines
+            let mut stack = vec![(root.id, String::new(), String::new())];
+            while let Some((idx, line_prefix, child_prefix)) = stack.pop() {
+                let node = self.linked_node(idx);
+                res.push_str(&line_prefix);
+                res.push_str(&node.value.to_string());
+                if let Some(annotation) = f(node) {
+                    res.push_str(&format!(" ({})", annotation));
+                }
+                res.push('\n');
+                let n_children = node.children.len();
+                for (i, &child) in node.children.iter().enumerate().rev() {
+                    let (connector, indent) = if i + 1 == n_children {
+                        ("└── ", "    ")
+                    } else {
+                        ("├── ", "│   ")
+                    };
+                    stack.push((
+                        child,
+                        format!("{}{}", child_prefix, connector),
+                        format!("{}{}", child_prefix, indent),
+                    ));
+                }
+            }
+        }
+        res
+    }
+
+    /// Export the tree in the Graphviz DOT language, with each node labelled by its value.
+    ///
+    /// The output can be rendered with e.g. `dot -Tsvg tree.dot -o tree.svg`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::ArenaTree;
+    ///
+    /// let mut tree = ArenaTree::new();
+    /// let root = tree.add_node("COM");
+    /// tree.add_child_node(root, "B").unwrap();
+    ///
+    /// assert_eq!(
+    ///     tree.to_dot(),
+    ///     "digraph {\n    0 [label=\"COM\"];\n    1 [label=\"B\"];\n    0 -> 1;\n}\n"
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut res = String::from("digraph {\n");
+        for node in self.iter() {
+            let label = node.value.to_string();
+            let label = label
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            res.push_str(&format!("    {} [label=\"{}\"];\n", node.id, label));
+        }
+        for node in self.iter() {
+            for child in &node.children {
+                res.push_str(&format!("    {} -> {};\n", node.id, child));
+            }
+        }
+        res.push_str("}\n");
+        res
+    }
+}
+
+impl<T> fmt::Display for ArenaTree<T>
+where
+    T: PartialEq + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+/// Value of a node in a tree of nested lists, as built by `ArenaTree::from_nested`
+///
+/// The elements of a list are the children of its node, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nested<L> {
+    /// A list, whose elements are the children of the node
+    List,
+    /// A single value which is not a list
+    Leaf(L),
+}
+
+/// Characters which open, close and separate the elements of nested lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delimiters {
+    /// Character which opens a list
+    pub open: char,
+    /// Character which closes a list
+    pub close: char,
+    /// Character between the elements of a list
+    pub separator: char,
+}
+
+impl Default for Delimiters {
+    /// Square brackets and commas, as in `[[1],[2,3,4]]`
+    fn default() -> Self {
+        Delimiters {
+            open: '[',
+            close: ']',
+            separator: ',',
+        }
+    }
+}
+
+/// Error returned when text does not hold a well formed nested list.
+#[derive(Debug, PartialEq)]
+pub enum ParseNestedError {
+    /// Raised when a character appears where it is not allowed, e.g. a separator with no
+    /// element before it
+    UnexpectedChar {
+        /// The character found
+        found: char,
+        /// 0-based byte offset of the character
+        position: usize,
+    },
+    /// Raised when the text ends before every list has been closed
+    UnexpectedEnd,
+    /// Raised when the leaf parser fails
+    InvalidLeaf {
+        /// Text of the leaf
+        text: String,
+        /// 0-based byte offset of the leaf
+        position: usize,
+        /// Description of the underlying parse error
+        message: String,
+    },
+    /// Raised when there is text left over after the outermost element
+    TrailingInput {
+        /// 0-based byte offset of the leftover text
+        position: usize,
+    },
+}
+
+impl fmt::Display for ParseNestedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseNestedError::UnexpectedChar { found, position } => {
+                write!(f, "unexpected {:?} at position {}", found, position)
+            }
+            ParseNestedError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseNestedError::InvalidLeaf {
+                text,
+                position,
+                message,
+            } => write!(
+                f,
+                "failed to parse leaf {:?} at position {}: {}",
+                text, position, message
+            ),
+            ParseNestedError::TrailingInput { position } => {
+                write!(f, "unexpected trailing input at position {}", position)
+            }
+        }
+    }
+}
+
+impl Error for ParseNestedError {}
+
+// Pieces of output still to be written by `ArenaTree::to_nested`
+enum NestedToken {
+    Node(NodeId),
+    Separator,
+    Close,
+}
+
+impl<L> ArenaTree<Nested<L>>
+where
+    L: PartialEq,
+{
+    /// Build a tree from a nested list such as `[[1],[2,3,4]]`, with the outermost element as
+    /// its root.
+    ///
+    /// Anything between delimiters is a leaf and is parsed by parse_leaf, and whitespace around
+    /// elements is ignored. The outermost element may be a bare leaf. Will return error if the
+    /// text is not a single well formed element or a leaf fails to parse.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc_utils::tree::{ArenaTree, Delimiters, Nested};
+    ///
+    /// let tree = ArenaTree::from_nested("[[1],[2, 3],[]]", Delimiters::default(), str::parse::<u8>).unwrap();
+    /// let root = tree.roots().next().unwrap();
+    /// let second = root.children()[1];
+    ///
+    /// assert_eq!(root.value, Nested::List);
+    /// assert_eq!(tree[tree.get_node(second).unwrap().children()[1]], Nested::Leaf(3));
+    ///
+    /// let snailfish = Delimiters { open: '(', close: ')', separator: ' ' };
+    /// assert_eq!(tree.to_nested(root.id(), snailfish).unwrap(), "((1) (2 3) ())");
+    /// ```
+    pub fn from_nested<F, E>(
+        input: &str,
+        delimiters: Delimiters,
+        parse_leaf: F,
+    ) -> Result<Self, ParseNestedError>
+    where
+        F: FnMut(&str) -> Result<L, E>,
+        E: fmt::Display,
+    {
+        let mut tree = ArenaTree::new();
+        tree.add_nested(input, delimiters, parse_leaf)?;
+        Ok(tree)
+    }
+
+    /// Parse a nested list like `from_nested` and add it to this tree as a new root, so that
+    /// many lists can share one tree. Returns the id of the new root.
+    ///
+    /// On error the tree is left unchanged.
+    pub fn add_nested<F, E>(
+        &mut self,
+        input: &str,
+        delimiters: Delimiters,
+        mut parse_leaf: F,
+    ) -> Result<NodeId, ParseNestedError>
+    where
+        F: FnMut(&str) -> Result<L, E>,
+        E: fmt::Display,
+    {
+        let Delimiters {
+            open,
+            close,
+            separator,
+        } = delimiters;
+        let mut chars = input.char_indices().peekable();
+        let mut root = None;
+        // Lists which have been opened but not yet closed
+        let mut open_lists: Vec<NodeId> = Vec::new();
+        let mut expect_element = true;
+        let result = loop {
+            while let Some(&(_, c)) = chars.peek() {
+                // A whitespace separator has to be kept so that it can be seen
+                if !c.is_whitespace() || (c == separator && !expect_element) {
+                    break;
+                }
+                chars.next();
+            }
+            if !expect_element && open_lists.is_empty() {
+                break match chars.next() {
+                    Some((position, _)) => Err(ParseNestedError::TrailingInput { position }),
+                    None => Ok(()),
+                };
+            }
+            let Some(&(position, c)) = chars.peek() else {
+                break Err(ParseNestedError::UnexpectedEnd);
+            };
+            if expect_element {
+                if c == close && open_lists.is_empty() || c == separator {
+                    break Err(ParseNestedError::UnexpectedChar { found: c, position });
+                }
+                if c == close {
+                    // Only an empty list can close where an element is expected
+                    let list = *open_lists.last().expect("A list is open");
+                    if !self.linked_node(list).children.is_empty() {
+                        break Err(ParseNestedError::UnexpectedChar { found: c, position });
+                    }
+                    chars.next();
+                    open_lists.pop();
+                    expect_element = false;
+                    continue;
+                }
+                let value = if c == open {
+                    chars.next();
+                    Nested::List
+                } else {
+                    let mut end = position;
+                    while let Some(&(i, c)) = chars.peek() {
+                        if c == open || c == close || c == separator || c.is_whitespace() {
+                            break;
+                        }
+                        end = i + c.len_utf8();
+                        chars.next();
+                    }
+                    let text = &input[position..end];
+                    match parse_leaf(text) {
+                        Ok(leaf) => Nested::Leaf(leaf),
+                        Err(e) => {
+                            break Err(ParseNestedError::InvalidLeaf {
+                                text: text.to_string(),
+                                position,
+                                message: e.to_string(),
+                            })
+                        }
+                    }
+                };
+                let is_list = value == Nested::List;
+                let idx = match open_lists.last() {
+                    Some(&parent) => self
+                        .add_child_node(parent, value)
+                        .expect("Open lists exist"),
+                    None => {
+                        let idx = self.add_node(value);
+                        root = Some(idx);
+                        idx
+                    }
+                };
+                if is_list {
+                    open_lists.push(idx);
+                } else {
+                    expect_element = false;
+                }
+            } else if c == separator {
+                chars.next();
+                while let Some(&(_, c)) = chars.peek() {
+                    if !c.is_whitespace() {
+                        break;
+                    }
+                    chars.next();
+                }
+                // A whitespace separator before a close is only padding
+                expect_element =
+                    !(separator.is_whitespace() && chars.peek().map(|&(_, c)| c) == Some(close));
+            } else if c == close {
+                chars.next();
+                open_lists.pop();
+            } else {
+                break Err(ParseNestedError::UnexpectedChar { found: c, position });
+            }
+        };
+        match result {
+            Ok(()) => Ok(root.expect("An element was parsed")),
+            Err(e) => {
+                if let Some(root) = root {
+                    self.remove_subtree(root).expect("Root exists");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Write the subtree rooted at idx as a nested list, the inverse of `from_nested`.
+    ///
+    /// Will return error if the node does not exist in the tree.
+    pub fn to_nested(&self, idx: NodeId, delimiters: Delimiters) -> Result<String, NodeDoesNotExist>
+    where
+        L: fmt::Display,
+    {
+        self.get_node(idx)?;
+        let mut res = String::new();
+        let mut stack = vec![NestedToken::Node(idx)];
+        while let Some(token) = stack.pop() {
+            match token {
+                NestedToken::Node(idx) => {
+                    let node = self.linked_node(idx);
+                    match &node.value {
+                        Nested::Leaf(leaf) => res.push_str(&leaf.to_string()),
+                        Nested::List => {
+                            res.push(delimiters.open);
+                            stack.push(NestedToken::Close);
+                            for (i, &child) in node.children.iter().enumerate().rev() {
+                                stack.push(NestedToken::Node(child));
+                                if i > 0 {
+                                    stack.push(NestedToken::Separator);
+                                }
+                            }
+                        }
+                    }
+                }
+                NestedToken::Separator => res.push(delimiters.separator),
+                NestedToken::Close => res.push(delimiters.close),
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Pre-order iterator over a subtree of an ArenaTree<T>
+///
+/// Created by `ArenaTree::pre_order` and `ArenaTree::descendants`.
+pub struct PreOrder<'a, T>
+where
+    T: PartialEq,
+{
+    tree: &'a ArenaTree<T>,
+    stack: Vec<NodeId>,
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T>
+where
+    T: PartialEq,
+{
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.tree.linked_node(self.stack.pop()?);
+        self.stack.extend(node.children.iter().rev());
+        Some(node)
+    }
+}
+
+/// Post-order iterator over a subtree of an ArenaTree<T>
+///
+/// Created by `ArenaTree::post_order`.
+pub struct PostOrder<'a, T>
+where
+    T: PartialEq,
+{
+    tree: &'a ArenaTree<T>,
+    // Each entry records whether the node's children have already been pushed
+    stack: Vec<(NodeId, bool)>,
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T>
+where
+    T: PartialEq,
+{
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (idx, expanded) = self.stack.pop()?;
+            let node = self.tree.linked_node(idx);
+            if expanded {
+                return Some(node);
+            }
+            self.stack.push((idx, true));
+            self.stack
+                .extend(node.children.iter().rev().map(|&child| (child, false)));
+        }
+    }
+}
+
+/// Breadth-first iterator over a subtree of an ArenaTree<T>
+///
+/// Created by `ArenaTree::breadth_first`.
+pub struct BreadthFirst<'a, T>
+where
+    T: PartialEq,
+{
+    tree: &'a ArenaTree<T>,
+    queue: VecDeque<NodeId>,
+}
+
+impl<'a, T> Iterator for BreadthFirst<'a, T>
+where
+    T: PartialEq,
+{
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.tree.linked_node(self.queue.pop_front()?);
+        self.queue.extend(node.children.iter());
+        Some(node)
+    }
+}
+
+/// Iterator over the ancestors of a node in an ArenaTree<T>
+///
+/// Created by `ArenaTree::ancestors`.
+pub struct Ancestors<'a, T>
+where
+    T: PartialEq,
+{
+    tree: &'a ArenaTree<T>,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> Iterator for Ancestors<'a, T>
+where
+    T: PartialEq,
+{
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.tree.linked_node(self.next?);
+        self.next = node.parent;
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ids for the first 40 slots of the tree, whether or not they hold a node
+    fn ids<T: PartialEq>(tree: &ArenaTree<T>) -> Vec<NodeId> {
+        (0..40).map(|idx| NodeId { tree: tree.id, idx }).collect()
+    }
+
+    #[test]
+    fn create_arena_tree_of_depth_three() {
+        let mut tree: ArenaTree<u32> = ArenaTree::new();
+        let idx0 = tree.add_node(10);
+        let idx1 = tree.add_node(20);
+        let idx2 = tree.add_node(21);
+        tree.register_parent_node(idx1, idx0).unwrap();
+        tree.register_parent_node(idx2, idx0).unwrap();
+        let idx3 = tree.add_child_node(idx1, 30).unwrap();
+
+        let n = ids(&tree);
+        assert_eq!((idx0, idx1, idx2, idx3), (n[0], n[1], n[2], n[3]));
+        assert_eq!(tree.get_node(idx0).unwrap().value, 10);
+        assert_eq!(tree.get_node(idx1).unwrap().parent().unwrap(), idx0);
+        assert_eq!(tree.get_node(idx3).unwrap().parent().unwrap(), idx1);
+        assert_eq!(tree.get_node(idx1).unwrap().children(), &vec![idx3]);
+    }
+
+    //         0
+    //       /   \
+    //      1     2
+    //     / \     \
+    //    3   4     5
+    //              |
+    //              6
+    fn sample_tree() -> ArenaTree<u32> {
+        let mut tree = ArenaTree::new();
+        let root = tree.add_node(0);
+        let one = tree.add_child_node(root, 1).unwrap();
+        let two = tree.add_child_node(root, 2).unwrap();
+        tree.add_child_node(one, 3).unwrap();
+        tree.add_child_node(one, 4).unwrap();
+        let five = tree.add_child_node(two, 5).unwrap();
+        tree.add_child_node(five, 6).unwrap();
+        tree
+    }
+
+    fn values<'a>(iter: impl Iterator<Item = &'a Node<u32>>) -> Vec<u32> {
+        iter.map(|node| node.value).collect()
+    }
+
+    #[test]
+    fn traversal_orders() {
+        let tree = sample_tree();
+        let n = ids(&tree);
+        assert_eq!(
+            values(tree.pre_order(n[0]).unwrap()),
+            vec![0, 1, 3, 4, 2, 5, 6]
+        );
+        assert_eq!(
+            values(tree.post_order(n[0]).unwrap()),
+            vec![3, 4, 1, 6, 5, 2, 0]
+        );
+        assert_eq!(
+            values(tree.breadth_first(n[0]).unwrap()),
+            vec![0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(values(tree.pre_order(n[2]).unwrap()), vec![2, 5, 6]);
+        assert_eq!(values(tree.post_order(n[1]).unwrap()), vec![3, 4, 1]);
+    }
+
+    #[test]
+    fn ancestors_and_descendants() {
+        let tree = sample_tree();
+        let n = ids(&tree);
+        assert_eq!(values(tree.ancestors(n[6]).unwrap()), vec![5, 2, 0]);
+        assert_eq!(values(tree.ancestors(n[0]).unwrap()), Vec::<u32>::new());
+        assert_eq!(values(tree.descendants(n[1]).unwrap()), vec![3, 4]);
+        assert_eq!(values(tree.descendants(n[6]).unwrap()), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn traversal_of_missing_node() {
+        let tree = sample_tree();
+        let n = ids(&tree);
+        assert!(tree.pre_order(n[7]).is_err());
+        assert!(tree.post_order(n[7]).is_err());
+        assert!(tree.breadth_first(n[7]).is_err());
+        assert!(tree.ancestors(n[7]).is_err());
+        assert!(tree.descendants(n[7]).is_err());
+    }
+
+    #[test]
+    fn ids_from_another_tree_are_rejected() {
+        let tree = sample_tree();
+        let mut other = sample_tree();
+        let other_root = other.add_node(10);
+        assert_ne!(other_root, ids(&tree)[7]);
+        assert_eq!(
+            tree.get_node(other_root).unwrap_err(),
+            NodeDoesNotExist { id: other_root }
+        );
+        assert_eq!(
+            other.move_subtree(other_root, ids(&tree)[0]),
+            Err(TreeError::NodeDoesNotExist(ids(&tree)[0]))
+        );
+    }
+
+    #[test]
+    fn errors_display_the_bad_id() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        let err: Box<dyn Error> = Box::new(tree.get_node(n[9]).unwrap_err());
+        assert_eq!(err.to_string(), "node 9 does not exist in the tree");
+        let err = tree.move_subtree(n[0], n[3]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot move node 0 under node 3 since it would create a cycle"
+        );
+    }
+
+    #[test]
+    fn index_node_values() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree[n[5]] += 10;
+        assert_eq!(tree[n[5]], 15);
+        *tree.get_mut(n[6]).unwrap() = 60;
+        assert_eq!(tree.get_node(n[6]).unwrap().value, 60);
+        assert!(tree.get_mut(n[7]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "node 7 does not exist in the tree")]
+    fn index_missing_node() {
+        let tree = sample_tree();
+        let _ = tree[ids(&tree)[7]];
+    }
+
+    #[test]
+    fn fold_up_subtree_sizes() {
+        let tree = sample_tree();
+        let sizes =
+            tree.fold_up(|_, children: &[&usize]| 1 + children.iter().copied().sum::<usize>());
+        let sizes: Vec<usize> = sizes.into_iter().flatten().collect();
+        assert_eq!(sizes, vec![7, 3, 3, 1, 1, 2, 1]);
+    }
+
+    #[test]
+    fn fold_down_depths_over_forest() {
+        let mut tree = sample_tree();
+        let other_root = tree.add_node(7);
+        tree.add_child_node(other_root, 8).unwrap();
+        let depths = tree.fold_down(|_, parent: Option<&u32>| parent.map_or(0, |d| d + 1));
+        let depths: Vec<u32> = depths.into_iter().flatten().collect();
+        assert_eq!(depths, vec![0, 1, 1, 2, 2, 2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn move_subtree_updates_both_parents() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree.move_subtree(n[5], n[1]).unwrap();
+        assert_eq!(tree.get_node(n[2]).unwrap().children(), &vec![]);
+        assert_eq!(
+            tree.get_node(n[1]).unwrap().children(),
+            &vec![n[3], n[4], n[5]]
+        );
+        assert_eq!(tree.get_node(n[5]).unwrap().parent(), Some(n[1]));
+        assert_eq!(
+            values(tree.pre_order(n[0]).unwrap()),
+            vec![0, 1, 3, 4, 5, 6, 2]
+        );
+
+        // Registering an existing child again leaves its position alone
+        tree.register_parent_node(n[3], n[1]).unwrap();
+        assert_eq!(
+            tree.get_node(n[1]).unwrap().children(),
+            &vec![n[3], n[4], n[5]]
+        );
+    }
+
+    #[test]
+    fn move_subtree_refuses_cycles() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        assert_eq!(
+            tree.move_subtree(n[2], n[6]),
+            Err(TreeError::WouldCreateCycle {
+                child: n[2],
+                parent: n[6]
+            })
+        );
+        assert_eq!(
+            tree.register_parent_node(n[4], n[4]),
+            Err(TreeError::WouldCreateCycle {
+                child: n[4],
+                parent: n[4]
+            })
+        );
+        assert_eq!(
+            tree.move_subtree(n[2], n[7]),
+            Err(TreeError::NodeDoesNotExist(n[7]))
+        );
+        assert_eq!(
+            values(tree.pre_order(n[0]).unwrap()),
+            vec![0, 1, 3, 4, 2, 5, 6]
+        );
+    }
+
+    #[test]
+    fn detach_makes_a_root() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree.detach(n[5]).unwrap();
+        assert_eq!(tree.get_node(n[5]).unwrap().parent(), None);
+        assert_eq!(values(tree.pre_order(n[0]).unwrap()), vec![0, 1, 3, 4, 2]);
+        let depths = tree.fold_down(|_, parent: Option<&u32>| parent.map_or(0, |d| d + 1));
+        assert_eq!(depths[6], Some(1));
+    }
+
+    #[test]
+    fn remove_node_splices_children_into_parent() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        assert_eq!(tree.remove_node(n[1]).unwrap(), 1);
+        assert_eq!(tree.len(), 6);
+        assert_eq!(
+            tree.get_node(n[0]).unwrap().children(),
+            &vec![n[3], n[4], n[2]]
+        );
+        assert_eq!(tree.get_node(n[4]).unwrap().parent(), Some(n[0]));
+        assert!(tree.get_node(n[1]).is_err());
+        assert!(tree.pre_order(n[1]).is_err());
+        assert!(tree.remove_node(n[1]).is_err());
+
+        // Removing a root turns its children into roots
+        tree.remove_node(n[0]).unwrap();
+        assert_eq!(tree.get_node(n[2]).unwrap().parent(), None);
+        assert_eq!(values(tree.iter()), vec![2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn remove_subtree_keeps_ids_stable() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        assert_eq!(tree.remove_subtree(n[2]).unwrap(), vec![2, 5, 6]);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.get_node(n[0]).unwrap().children(), &vec![n[1]]);
+        assert!(tree.get_node(n[6]).is_err());
+
+        let new = tree.add_child_node(n[4], 7).unwrap();
+        assert_eq!(new, n[7]);
+        assert_eq!(tree[n[4]], 4);
+        let sizes =
+            tree.fold_up(|_, children: &[&usize]| 1 + children.iter().copied().sum::<usize>());
+        assert_eq!(
+            sizes,
+            vec![
+                Some(5),
+                Some(4),
+                None,
+                Some(1),
+                Some(2),
+                None,
+                None,
+                Some(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn roots_of_a_forest() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        let other_root = tree.add_node(7);
+        tree.add_child_node(other_root, 8).unwrap();
+        assert_eq!(values(tree.roots()), vec![0, 7]);
+        assert_eq!(tree.root_of(n[6]).unwrap(), n[0]);
+        assert_eq!(tree.root_of(n[8]).unwrap(), other_root);
+        assert_eq!(tree.root_of(n[0]).unwrap(), n[0]);
+        assert!(tree.root_of(n[9]).is_err());
+
+        tree.detach(n[5]).unwrap();
+        assert_eq!(values(tree.roots()), vec![0, 7, 5]);
+        tree.move_subtree(other_root, n[5]).unwrap();
+        assert_eq!(values(tree.roots()), vec![0, 5]);
+        // The children of a removed root take its place among the roots
+        tree.remove_node(n[0]).unwrap();
+        assert_eq!(values(tree.roots()), vec![1, 2, 5]);
+        tree.remove_subtree(n[5]).unwrap();
+        assert_eq!(values(tree.roots()), vec![1, 2]);
+        tree.validate().unwrap();
+    }
+
+    #[test]
+    fn merge_remaps_ids() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        let mut other = sample_tree();
+        let m = ids(&other);
+        other.remove_node(m[1]).unwrap();
+        other.add_node(7);
+
+        let mapping = tree.merge_under(n[6], other).unwrap();
+        tree.validate().unwrap();
+        assert_eq!(mapping.len(), 7);
+        assert_eq!(mapping[&m[0]], n[7]);
+        assert_eq!(mapping[&m[2]], n[8]);
+        assert_eq!(
+            values(tree.descendants(n[6]).unwrap()),
+            vec![0, 3, 4, 2, 5, 6, 7]
+        );
+        assert_eq!(tree.get_node(mapping[&m[3]]).unwrap().parent(), Some(n[7]));
+        assert_eq!(values(tree.roots()), vec![0]);
+
+        let third = sample_tree();
+        let k = ids(&third);
+        let mapping = tree.merge(third);
+        tree.validate().unwrap();
+        assert_eq!(values(tree.roots()), vec![0, 0]);
+        assert_eq!(tree.len(), 21);
+        assert_eq!(tree.root_of(mapping[&k[6]]).unwrap(), n[14]);
+
+        assert!(tree.merge_under(n[30], sample_tree()).is_err());
+        assert_eq!(tree.len(), 21);
+    }
+
+    #[test]
+    fn validate_finds_broken_links() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree.validate().unwrap();
+
+        tree.arena[4].as_mut().unwrap().children.push(n[5]);
+        assert_eq!(
+            tree.validate(),
+            Err(InvariantViolation::ParentChildMismatch {
+                parent: n[4],
+                child: n[5]
+            })
+        );
+
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree.arena[3].as_mut().unwrap().children.push(n[9]);
+        assert_eq!(
+            tree.validate(),
+            Err(InvariantViolation::DanglingLink {
+                from: n[3],
+                to: n[9]
+            })
+        );
+
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree.roots.push(n[2]);
+        assert_eq!(tree.validate(), Err(InvariantViolation::RootMismatch(n[2])));
+
+        // Make 1 and 3 each other's parent, cut off from the root
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        tree.arena[0]
+            .as_mut()
+            .unwrap()
+            .children
+            .retain(|&c| c != n[1]);
+        tree.arena[1].as_mut().unwrap().parent = Some(n[3]);
+        tree.arena[3].as_mut().unwrap().children.push(n[1]);
+        assert_eq!(tree.validate(), Err(InvariantViolation::Unreachable(n[1])));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Named(&'static str);
+
+    impl Keyed for Named {
+        type Key = &'static str;
+
+        fn key(&self) -> &&'static str {
+            &self.0
+        }
+    }
+
+    //      /
+    //    /   \
+    //   a     b
+    //  / \
+    // c   b
+    fn keyed_tree() -> KeyedArenaTree<Named> {
+        let mut tree = KeyedArenaTree::new();
+        let root = tree.add_node(Named("/"));
+        let a = tree.add_child_node(root, Named("a")).unwrap();
+        tree.add_child_node(root, Named("b")).unwrap();
+        tree.add_child_node(a, Named("c")).unwrap();
+        tree.add_child_node(a, Named("b")).unwrap();
+        tree
+    }
+
+    fn id(node: Result<Option<&Node<Named>>, NodeDoesNotExist>) -> Option<NodeId> {
+        node.unwrap().map(|node| node.id())
+    }
+
+    #[test]
+    fn find_child_linear_and_indexed() {
+        let keyed = keyed_tree();
+        let n = ids(&keyed);
+        assert_eq!(id(keyed.find_child_by_key(n[1], "b")), Some(n[4]));
+        assert_eq!(id(keyed.find_child_by_key(n[1], "a")), None);
+        assert_eq!(
+            id(keyed.find_child(n[0], |node| node.children().is_empty())),
+            Some(n[2])
+        );
+        assert!(keyed.find_child_by_key(n[5], "a").is_err());
+
+        let tree = keyed.into_inner();
+        assert_eq!(id(tree.find_child_by_key(n[1], "b")), Some(n[4]));
+        assert_eq!(id(tree.find_child_by_key(n[1], "a")), None);
+        assert!(tree.find_child_by_key(n[5], "a").is_err());
+    }
+
+    #[test]
+    fn resolve_path_linear_and_indexed() {
+        let keyed = keyed_tree();
+        let n = ids(&keyed);
+        assert_eq!(id(keyed.resolve_path(n[0], ["a", "b"])), Some(n[4]));
+        assert_eq!(id(keyed.resolve_path(n[0], [] as [&str; 0])), Some(n[0]));
+        assert_eq!(id(keyed.resolve_path(n[0], ["b", "c"])), None);
+        assert!(keyed.resolve_path(n[9], ["a"]).is_err());
+
+        let tree = keyed.into_inner();
+        assert_eq!(id(tree.resolve_path(n[0], ["a", "c"])), Some(n[3]));
+        assert_eq!(id(tree.resolve_path(n[0], ["c"])), None);
+    }
+
+    #[test]
+    fn keyed_tree_index_follows_changes() {
+        let mut tree = keyed_tree();
+        let n = ids(&tree);
+        // Moving a second "b" under the root leaves the first one in the index
+        tree.move_subtree(n[4], n[0]).unwrap();
+        assert_eq!(id(tree.find_child_by_key(n[0], "b")), Some(n[2]));
+        assert_eq!(id(tree.find_child_by_key(n[1], "b")), None);
+
+        // Detaching the first "b" exposes the second
+        tree.detach(n[2]).unwrap();
+        assert_eq!(id(tree.find_child_by_key(n[0], "b")), Some(n[4]));
+
+        // Removing "a" splices "c" into the root
+        tree.remove_node(n[1]).unwrap();
+        assert_eq!(id(tree.find_child_by_key(n[0], "a")), None);
+        assert_eq!(id(tree.find_child_by_key(n[0], "c")), Some(n[3]));
+
+        assert_eq!(tree.remove_subtree(n[0]).unwrap().len(), 3);
+        assert!(tree.find_child_by_key(n[0], "c").is_err());
+        assert_eq!(tree.len(), 1);
+    }
+
+    #[test]
+    fn keyed_tree_merge() {
+        let mut tree = keyed_tree();
+        let n = ids(&tree);
+        let other = keyed_tree().into_inner();
+        let m = ids(&other);
+        let mapping = tree.merge_under(n[2], other).unwrap();
+        tree.validate().unwrap();
+        assert_eq!(id(tree.find_child_by_key(n[2], "/")), Some(mapping[&m[0]]));
+        assert_eq!(
+            id(tree.resolve_path(n[0], ["b", "/", "a", "c"])),
+            Some(mapping[&m[3]])
+        );
+
+        let other = keyed_tree().into_inner();
+        let m = ids(&other);
+        let mapping = tree.merge(other);
+        assert_eq!(
+            id(tree.find_child_by_key(mapping[&m[1]], "b")),
+            Some(mapping[&m[4]])
+        );
+    }
+
+    #[test]
+    fn render_forest() {
+        let mut tree = sample_tree();
+        tree.add_node(7);
+        let rendered = tree.render_with(|node| (node.value % 2 == 1).then_some("odd"));
+        let expected = [
+            "0",
+            "├── 1 (odd)",
+            "│   ├── 3 (odd)",
+            "│   └── 4",
+            "└── 2",
+            "    └── 5 (odd)",
+            "        └── 6",
+            "7 (odd)",
+        ];
+        assert_eq!(rendered.lines().collect::<Vec<_>>(), expected);
+        assert_eq!(tree.to_string(), tree.render());
+    }
+
+    #[test]
+    fn to_dot_escapes_labels() {
+        let mut tree = ArenaTree::new();
+        let root = tree.add_node("say \"hi\"");
+        tree.add_child_node(root, "a\\b").unwrap();
+        assert_eq!(
+            tree.to_dot(),
+            "digraph {\n    0 [label=\"say \\\"hi\\\"\"];\n    1 [label=\"a\\\\b\"];\n    0 -> 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn depth_and_height() {
+        let tree = sample_tree();
+        let n = ids(&tree);
+        assert_eq!(tree.depth(n[0]).unwrap(), 0);
+        assert_eq!(tree.depth(n[6]).unwrap(), 3);
+        assert_eq!(tree.height(n[0]).unwrap(), 3);
+        assert_eq!(tree.height(n[1]).unwrap(), 1);
+        assert_eq!(tree.height(n[6]).unwrap(), 0);
+        assert!(tree.depth(n[7]).is_err());
+        assert!(tree.height(n[7]).is_err());
+    }
+
+    #[test]
+    fn lowest_common_ancestor_and_distance() {
+        let mut tree = sample_tree();
+        let n = ids(&tree);
+        let other_root = tree.add_node(7);
+        let table = tree.ancestor_table();
+        for (a, b, lca, distance) in [
+            (n[3], n[4], Some(n[1]), Some(2)),
+            (n[3], n[6], Some(n[0]), Some(5)),
+            (n[5], n[6], Some(n[5]), Some(1)),
+            (n[6], n[2], Some(n[2]), Some(2)),
+            (n[4], n[4], Some(n[4]), Some(0)),
+            (n[6], other_root, None, None),
+        ] {
+            assert_eq!(tree.lowest_common_ancestor(a, b).unwrap(), lca);
+            assert_eq!(table.lowest_common_ancestor(a, b).unwrap(), lca);
+            assert_eq!(tree.distance(a, b).unwrap(), distance);
+            assert_eq!(table.distance(a, b).unwrap(), distance);
+        }
+        assert!(tree.lowest_common_ancestor(n[0], n[8]).is_err());
+        assert!(table.lowest_common_ancestor(n[8], n[0]).is_err());
+        assert!(table.distance(n[0], n[8]).is_err());
+    }
+
+    #[test]
+    fn ancestor_table_on_long_path() {
+        let mut tree = ArenaTree::new();
+        let mut path = vec![tree.add_node(0)];
+        for value in 1..100 {
+            path.push(tree.add_child_node(path[value - 1], value).unwrap());
+        }
+        let branch = tree.add_child_node(path[37], 100).unwrap();
+        tree.remove_node(path[50]).unwrap();
+        let table = tree.ancestor_table();
+        assert_eq!(table.depth(path[99]).unwrap(), 98);
+        assert_eq!(table.ancestor(path[99], 0).unwrap(), Some(path[99]));
+        assert_eq!(table.ancestor(path[99], 61).unwrap(), Some(path[37]));
+        assert_eq!(table.ancestor(path[99], 98).unwrap(), Some(path[0]));
+        assert_eq!(table.ancestor(path[99], 99).unwrap(), None);
+        assert_eq!(
+            table.lowest_common_ancestor(path[99], branch).unwrap(),
+            Some(path[37])
+        );
+        assert_eq!(table.distance(path[99], branch).unwrap(), Some(62));
+        assert!(table.depth(path[50]).is_err());
+        assert!(table.ancestor(path[50], 1).is_err());
+    }
+
+    #[test]
+    fn nested_round_trip() {
+        let mut tree = ArenaTree::new();
+        let inputs = ["[1,[2,[3,[4,[5,6,7]]]],8,9]", "[[[]]]", "[]", "10"];
+        let roots: Vec<NodeId> = inputs
+            .iter()
+            .map(|input| {
+                tree.add_nested(input, Delimiters::default(), str::parse::<u32>)
+                    .unwrap()
+            })
+            .collect();
+        for (&root, input) in roots.iter().zip(inputs) {
+            assert_eq!(tree.to_nested(root, Delimiters::default()).unwrap(), input);
+        }
+        assert_eq!(tree.roots().count(), 4);
+        assert_eq!(tree[roots[3]], Nested::Leaf(10));
+        assert_eq!(tree.height(roots[1]).unwrap(), 2);
+        tree.validate().unwrap();
+    }
+
+    #[test]
+    fn nested_custom_delimiters_and_whitespace() {
+        let sexp = Delimiters {
+            open: '(',
+            close: ')',
+            separator: ' ',
+        };
+        let tree = ArenaTree::from_nested(
+            "( define  (square x)\n  (* x x) )",
+            sexp,
+            |s: &str| -> Result<String, std::convert::Infallible> { Ok(s.to_string()) },
+        )
+        .unwrap();
+        let root = tree.roots().next().unwrap().id();
+        assert_eq!(
+            tree.to_nested(root, sexp).unwrap(),
+            "(define (square x) (* x x))"
+        );
+        let tree =
+            ArenaTree::from_nested(" [ 1 ,2 , [ ] ]\n", Delimiters::default(), str::parse::<u8>)
+                .unwrap();
+        let root = tree.roots().next().unwrap().id();
+        assert_eq!(
+            tree.to_nested(root, Delimiters::default()).unwrap(),
+            "[1,2,[]]"
+        );
+    }
+
+    #[test]
+    fn nested_errors() {
+        let parse = |input| {
+            ArenaTree::from_nested(input, Delimiters::default(), str::parse::<u8>).map(|_| ())
+        };
+        assert_eq!(
+            parse("[1,,2]"),
+            Err(ParseNestedError::UnexpectedChar {
+                found: ',',
+                position: 3
+            })
+        );
+        assert_eq!(
+            parse("[1,]"),
+            Err(ParseNestedError::UnexpectedChar {
+                found: ']',
+                position: 3
+            })
+        );
+        assert_eq!(
+            parse("[1 2]"),
+            Err(ParseNestedError::UnexpectedChar {
+                found: '2',
+                position: 3
+            })
+        );
+        assert_eq!(parse("[[1]"), Err(ParseNestedError::UnexpectedEnd));
+        assert_eq!(parse(""), Err(ParseNestedError::UnexpectedEnd));
+        assert_eq!(
+            parse("[1]]"),
+            Err(ParseNestedError::TrailingInput { position: 3 })
+        );
+        match parse("[1,256]") {
+            Err(ParseNestedError::InvalidLeaf { text, position, .. }) => {
+                assert_eq!((text.as_str(), position), ("256", 3))
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_nested_leaf_error_message() {
+        let err =
+            ArenaTree::from_nested("[1,x]", Delimiters::default(), str::parse::<u8>).unwrap_err();
+        match &err {
+            ParseNestedError::InvalidLeaf { message, .. } => {
+                assert_eq!(message, "invalid digit found in string")
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "failed to parse leaf \"x\" at position 3: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn add_nested_leaves_tree_unchanged_on_error() {
+        let mut tree = ArenaTree::new();
+        let root = tree
+            .add_nested("[1]", Delimiters::default(), str::parse::<u8>)
+            .unwrap();
+        assert!(tree
+            .add_nested("[[2],[3", Delimiters::default(), str::parse::<u8>)
+            .is_err());
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.roots().map(|n| n.id()).collect::<Vec<_>>(), vec![root]);
+        tree.validate().unwrap();
+        assert!(tree
+            .to_nested(ids(&tree)[2], Delimiters::default())
+            .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::super::*;
+        use super::{ids, sample_tree, values};
+
+        #[test]
+        fn round_trip_keeps_ids_and_tombstones() {
+            let mut tree = sample_tree();
+            let n = ids(&tree);
+            tree.remove_node(n[1]).unwrap();
+            tree.detach(n[5]).unwrap();
+            let json = serde_json::to_string(&tree).unwrap();
+
+            let copy: ArenaTree<u32> = serde_json::from_str(&json).unwrap();
+            copy.validate().unwrap();
+            let m = ids(&copy);
+            assert_eq!(copy.len(), tree.len());
+            assert!(copy.get_node(m[1]).is_err());
+            assert_eq!(
+                values(copy.pre_order(m[0]).unwrap()),
+                values(tree.pre_order(n[0]).unwrap())
+            );
+            assert_eq!(
+                copy.roots().map(|node| node.id()).collect::<Vec<_>>(),
+                vec![m[0], m[5]]
+            );
+            // The copy is a different tree, so ids are not interchangeable
+            assert!(copy.get_node(n[0]).is_err());
+            assert_eq!(serde_json::to_string(&copy).unwrap(), json);
+        }
+
+        #[test]
+        fn node_serializes_ids_as_indices() {
+            let tree = sample_tree();
+            let node = tree.get_node(ids(&tree)[1]).unwrap();
+            assert_eq!(
+                serde_json::to_string(node).unwrap(),
+                r#"{"id":1,"value":1,"parent":0,"children":[3,4]}"#
+            );
+            let node: Node<u32> =
+                serde_json::from_str(r#"{"id":1,"value":1,"parent":0,"children":[3,4]}"#).unwrap();
+            assert_eq!(node.id().index(), 1);
+            assert_eq!(node.parent().map(NodeId::index), Some(0));
+        }
+
+        #[test]
+        fn malformed_trees_are_rejected() {
+            let parse = |json| serde_json::from_str::<ArenaTree<u32>>(json);
+            // Child which does not list its parent
+            assert!(parse(r#"{"nodes":[{"id":0,"value":0,"parent":null,"children":[1]},{"id":1,"value":1,"parent":null,"children":[]}],"roots":[0,1]}"#).is_err());
+            // Node stored in the wrong slot
+            assert!(parse(
+                r#"{"nodes":[null,{"id":0,"value":0,"parent":null,"children":[]}],"roots":[0]}"#
+            )
+            .is_err());
+            // Root which does not exist
+            assert!(parse(r#"{"nodes":[],"roots":[3]}"#).is_err());
+            assert!(parse(r#"{"nodes":[null],"roots":[]}"#).unwrap().is_empty());
+        }
     }
 }
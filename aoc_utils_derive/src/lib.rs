@@ -0,0 +1,229 @@
+#![warn(missing_docs)]
+
+//! Derive macros for `aoc_utils`. Use these through `aoc_utils` with the `derive` feature
+//! rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// A pattern split into literal text and the names of the fields between them.
+///
+/// There is always exactly one more literal than there are fields, so that `literals[i]` comes
+/// before `fields[i]` and the last literal comes after the last field.
+#[derive(Debug, PartialEq)]
+struct Pattern {
+    literals: Vec<String>,
+    fields: Vec<String>,
+}
+
+/// Split a pattern like `"{x},{y} -> {x2},{y2}"` into literals and field names.
+///
+/// `{{` and `}}` are literal braces. Two fields must be separated by some literal text,
+/// otherwise there would be no way to tell where one ends and the next begins.
+fn parse_pattern(pattern: &str) -> Result<Pattern, String> {
+    let mut literals = vec![String::new()];
+    let mut fields = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literals.last_mut().unwrap().push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literals.last_mut().unwrap().push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("unclosed `{{{}` in pattern", name)),
+                    }
+                }
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return Err("empty `{}` in pattern".to_string());
+                }
+                if !fields.is_empty() && literals.last().unwrap().is_empty() {
+                    return Err(format!(
+                        "field `{}` directly follows another field; separate them with some text",
+                        name
+                    ));
+                }
+                if fields.contains(&name) {
+                    return Err(format!(
+                        "field `{}` appears in pattern more than once",
+                        name
+                    ));
+                }
+                fields.push(name);
+                literals.push(String::new());
+            }
+            '}' => return Err("unmatched `}` in pattern; use `}}` for a literal brace".to_string()),
+            c => literals.last_mut().unwrap().push(c),
+        }
+    }
+    Ok(Pattern { literals, fields })
+}
+
+/// Derive `FromStr` for a struct from a pattern given in a `#[pattern("...")]` attribute.
+///
+/// Each `{field}` in the pattern names a field of the struct, or an index like `{0}` for a
+/// tuple struct, and every field must appear exactly once. Fields are parsed with their own
+/// `FromStr` implementations, and each one's text runs up to the first occurrence of the
+/// literal text which follows it in the pattern, with surrounding whitespace trimmed. The error
+/// type is `aoc_utils::pattern::PatternError`, which reports which field failed and where.
+///
+/// # Examples
+/// ```ignore
+/// use aoc_utils::pattern::FromPattern;
+///
+/// #[derive(FromPattern)]
+/// #[pattern("{x1},{y1} -> {x2},{y2}")]
+/// struct Line {
+///     x1: i32,
+///     y1: i32,
+///     x2: i32,
+///     y2: i32,
+/// }
+///
+/// let line: Line = "0,9 -> 5,9".parse().unwrap();
+/// ```
+#[proc_macro_derive(FromPattern, attributes(pattern))]
+pub fn derive_from_pattern(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("pattern"))
+        .ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "FromPattern requires a #[pattern(\"...\")] attribute",
+            )
+        })?;
+    let lit: LitStr = attr.parse_args()?;
+    let pattern = parse_pattern(&lit.value()).map_err(|e| syn::Error::new(lit.span(), e))?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "FromPattern can only be derived for structs",
+            ))
+        }
+    };
+    let names: Vec<String> = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|f| f.ident.as_ref().unwrap().to_string())
+            .collect(),
+        Fields::Unnamed(unnamed) => (0..unnamed.unnamed.len()).map(|i| i.to_string()).collect(),
+        Fields::Unit => Vec::new(),
+    };
+    for name in &names {
+        if !pattern.fields.contains(name) {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!("field `{}` does not appear in pattern", name),
+            ));
+        }
+    }
+    for name in &pattern.fields {
+        if !names.contains(name) {
+            return Err(syn::Error::new(
+                lit.span(),
+                format!("pattern refers to unknown field `{}`", name),
+            ));
+        }
+    }
+
+    let literals = &pattern.literals;
+    let field_names = &pattern.fields;
+    let parse_field = |name: &String| {
+        let i = pattern.fields.iter().position(|f| f == name).unwrap();
+        quote! { ::aoc_utils::pattern::parse_capture(&captures[#i], #name)? }
+    };
+    let construct = match fields {
+        Fields::Named(_) => {
+            let idents = names.iter().map(|name| format_ident!("{}", name));
+            let values = names.iter().map(parse_field);
+            quote! { Self { #(#idents: #values),* } }
+        }
+        Fields::Unnamed(_) => {
+            let values = names.iter().map(parse_field);
+            quote! { Self(#(#values),*) }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = ::aoc_utils::pattern::PatternError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                #[allow(unused_variables)]
+                let captures = ::aoc_utils::pattern::match_pattern(
+                    s,
+                    &[#(#literals),*],
+                    &[#(#field_names),*],
+                )?;
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pattern_fields_and_literals() {
+        assert_eq!(
+            parse_pattern("{x},{y} -> {x2},{y2}"),
+            Ok(Pattern {
+                literals: vec!["", ",", " -> ", ",", ""]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                fields: vec!["x", "y", "x2", "y2"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_pattern_escaped_braces() {
+        let pattern = parse_pattern("{{{0}}}").unwrap();
+        assert_eq!(pattern.literals, vec!["{", "}"]);
+        assert_eq!(pattern.fields, vec!["0"]);
+    }
+
+    #[test]
+    fn parse_pattern_errors() {
+        assert!(parse_pattern("{x}{y}").is_err());
+        assert!(parse_pattern("{x").is_err());
+        assert!(parse_pattern("x}").is_err());
+        assert!(parse_pattern("{}").is_err());
+        assert!(parse_pattern("{x},{x}").is_err());
+    }
+}
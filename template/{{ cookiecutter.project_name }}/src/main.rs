@@ -1,7 +1,5 @@
 use aoc_utils::input;
 
-const INPUT: &str = include_str!("../input.txt");
-
 fn part_one(input: &str) -> usize {
     todo!()
 }
@@ -12,10 +10,11 @@ fn part_two(input: &str) -> usize {
 }
 
 fn main() {
-    println!("Head of INPUT:\n{:?}", input::head(INPUT));
-    let part_one_solution = part_one(INPUT);
+    let input = input::load(env!("CARGO_MANIFEST_DIR")).unwrap_or_else(|e| panic!("{}", e));
+    println!("Input summary:\n{}", input::summarize(&input));
+    let part_one_solution = part_one(&input);
     println!("Solution to part one: {}", part_one_solution);
-    // let part_two_solution = part_two(INPUT);
+    // let part_two_solution = part_two(&input);
     // println!("Solution to part two: {}", part_two_solution);
 }
 